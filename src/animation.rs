@@ -0,0 +1,258 @@
+//! Watching a simulation in the terminal.
+//!
+//! A day started with `--animate` redraws its [`Simulation`] in place after
+//! every `recording.steps_per_frame` steps, waiting `recording.frame_delay_ms`
+//! between frames. The keys control the animation:
+//!
+//! - space pauses and resumes
+//! - `s` (or `n`) pauses and advances a single frame
+//! - `+` and `-` make the animation faster and slower
+//! - `q` or Ctrl-C quits
+
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::simulation::Simulation;
+
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+const CLEAR_LINE_END: &str = "\x1b[K";
+const CLEAR_SCREEN_END: &str = "\x1b[J";
+/// The byte Ctrl-C sends once the terminal no longer turns it into a signal.
+const CTRL_C: u8 = 0x03;
+
+/// Animates the simulation on the terminal until it finishes or `q` or
+/// Ctrl-C is pressed.
+pub fn run<S: Simulation>(config: &Config, simulation: &mut S) {
+    let _raw_mode = RawMode::enable();
+    let keys = read_keys();
+    let stdout = io::stdout();
+    let frame_delay = Duration::from_millis(config.recording.frame_delay_ms);
+    run_with(
+        simulation,
+        keys,
+        stdout.lock(),
+        frame_delay,
+        config.recording.steps_per_frame,
+    )
+    .expect("Unable to use the terminal");
+}
+
+/// Drives the animation with the keys from `keys`, returning the number of
+/// steps taken.
+pub fn run_with<S, O>(
+    simulation: &mut S,
+    keys: Receiver<u8>,
+    mut output: O,
+    mut frame_delay: Duration,
+    steps_per_frame: usize,
+) -> io::Result<usize>
+where
+    S: Simulation,
+    O: Write,
+{
+    let mut steps = 0;
+    let mut paused = false;
+    let mut finished = false;
+    let mut keys_open = true;
+
+    write!(output, "{HIDE_CURSOR}{CLEAR_SCREEN}")?;
+    'animation: loop {
+        let state = match (finished, paused) {
+            (true, _) => "finished",
+            (false, true) => "paused",
+            (false, false) => "running",
+        };
+        draw(&mut output, &simulation.render(), steps, state, frame_delay)?;
+        if finished {
+            break;
+        }
+
+        // wait for the next frame, or for a key while paused
+        let deadline = Instant::now() + frame_delay;
+        let mut advance = !paused;
+        loop {
+            let key = match (paused, keys_open) {
+                (true, true) => keys.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                _ => keys.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            };
+            match key {
+                Ok(b'q' | CTRL_C) => break 'animation,
+                Ok(b' ') => {
+                    paused = !paused;
+                    advance = !paused;
+                    break;
+                }
+                Ok(b's' | b'n') => {
+                    paused = true;
+                    advance = true;
+                    break;
+                }
+                Ok(b'+') => frame_delay /= 2,
+                Ok(b'-') => frame_delay = (frame_delay * 2).max(Duration::from_millis(1)),
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    // without keys the animation simply runs to the end
+                    keys_open = false;
+                    paused = false;
+                    advance = true;
+                    thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                    break;
+                }
+            }
+        }
+
+        if advance {
+            for _ in 0..steps_per_frame.max(1) {
+                if !simulation.step() {
+                    finished = true;
+                    break;
+                }
+                steps += 1;
+            }
+        }
+    }
+    write!(output, "{SHOW_CURSOR}")?;
+    output.flush()?;
+    Ok(steps)
+}
+
+fn draw<O: Write>(
+    output: &mut O,
+    frame: &str,
+    steps: usize,
+    state: &str,
+    frame_delay: Duration,
+) -> io::Result<()> {
+    write!(output, "{CURSOR_HOME}")?;
+    for line in frame.lines() {
+        write!(output, "{line}{CLEAR_LINE_END}\r\n")?;
+    }
+    write!(
+        output,
+        "step {steps}  {state}  {}ms per frame  [space] pause  [s] step  [+/-] speed  [q] quit{CLEAR_LINE_END}\r\n{CLEAR_SCREEN_END}",
+        frame_delay.as_millis()
+    )?;
+    output.flush()
+}
+
+/// Sends the bytes read from stdin, until it is closed.
+fn read_keys() -> Receiver<u8> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for byte in io::stdin().lock().bytes() {
+            let Ok(byte) = byte else {
+                break;
+            };
+            if sender.send(byte).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Turns off line buffering and echo of the terminal, so single key presses
+/// arrive right away. Ctrl-C arrives as a key as well instead of killing the
+/// process, so the settings and the cursor are always restored when dropped.
+struct RawMode {
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+}
+
+impl RawMode {
+    #[cfg(unix)]
+    fn enable() -> Self {
+        // SAFETY: termios is plain old data, filled in by tcgetattr
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        // SAFETY: stdin stays open for the whole process
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            // not a terminal, keys are read as they come
+            return RawMode { original: None };
+        }
+        let original = termios;
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+        // SAFETY: as above, with settings derived from the current ones
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) };
+        RawMode {
+            original: Some(original),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn enable() -> Self {
+        RawMode {}
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(original) = self.original {
+            // SAFETY: restores the settings read in `enable`
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &original) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter(u32);
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> bool {
+            if self.0 == 5 {
+                return false;
+            }
+            self.0 += 1;
+            true
+        }
+
+        fn render(&self) -> String {
+            format!("count {}", self.0)
+        }
+    }
+
+    fn animate(keys: &[u8], counter: &mut Counter) -> (usize, String) {
+        let (sender, receiver) = mpsc::channel();
+        for &key in keys {
+            sender.send(key).unwrap();
+        }
+        drop(sender);
+        let mut output = vec![];
+        let steps = run_with(counter, receiver, &mut output, Duration::ZERO, 2).unwrap();
+        (steps, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_runs_to_the_end() {
+        let (steps, output) = animate(&[], &mut Counter(0));
+        assert_eq!(steps, 5);
+        assert!(output.contains("count 4\x1b[K\r\nstep 4  running"));
+        assert!(output.contains("count 5\x1b[K\r\nstep 5  finished"));
+        assert!(output.ends_with(SHOW_CURSOR));
+    }
+
+    #[test]
+    fn test_keys() {
+        // pause, a single frame of two steps, then quit
+        let mut counter = Counter(0);
+        let (steps, output) = animate(b" sq", &mut counter);
+        assert_eq!((steps, counter.0), (2, 2));
+        assert!(output.contains("step 0  paused"));
+        assert!(output.contains("step 2  paused"));
+        assert!(!output.contains("finished"));
+
+        let (steps, output) = animate(&[CTRL_C], &mut Counter(0));
+        assert_eq!(steps, 0);
+        assert!(output.ends_with(SHOW_CURSOR));
+    }
+}
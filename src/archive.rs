@@ -0,0 +1,430 @@
+//! A local archive of the puzzle descriptions.
+//!
+//! Puzzle pages saved from the browser are copied into the archive directory
+//! as `dayNN.html`. From there they can be rendered as plain text, and the
+//! `<pre><code>` example blocks and the emphasized example answers can be
+//! extracted into the inputs directory.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::progress::Answers;
+
+const TEXT_WIDTH: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Text(&'a str),
+    Open(&'a str),
+    Close(&'a str),
+}
+
+/// What was extracted from a puzzle page.
+#[derive(Debug, Default, PartialEq)]
+pub struct Examples {
+    pub inputs: Vec<String>,
+    /// The example answer of part one and, once unlocked, part two
+    pub answers: Vec<String>,
+}
+
+/// The files written by [`write_examples`], and the existing ones it kept.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExampleFiles {
+    pub written: Vec<PathBuf>,
+    pub kept: Vec<PathBuf>,
+}
+
+pub fn page_path(config: &Config, day: u8) -> PathBuf {
+    config
+        .resolve(&config.archive_dir)
+        .join(format!("day{day:02}.html"))
+}
+
+/// Copies a saved puzzle page into the archive.
+pub fn ingest(config: &Config, day: u8, html_file: &Path) -> Result<PathBuf, ArchiveError> {
+    let html = read(html_file)?;
+    if articles(&tokenize(&html)).is_empty() {
+        return Err(ArchiveError::NoPuzzle(html_file.to_path_buf()));
+    }
+
+    let path = page_path(config, day);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|source| ArchiveError::Io {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+    }
+    fs::write(&path, html).map_err(|source| ArchiveError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    Ok(path)
+}
+
+pub fn load(config: &Config, day: u8) -> Result<String, ArchiveError> {
+    read(&page_path(config, day))
+}
+
+/// Writes the example inputs as `test_dayNN.txt` (later blocks get a `_2`,
+/// `_3`, ... suffix) and the example answers to the examples file, where
+/// `aoc check` compares them to the answers of the days. Existing input
+/// files and answers are only replaced when `overwrite` is set.
+pub fn write_examples(
+    config: &Config,
+    day: u8,
+    examples: &Examples,
+    overwrite: bool,
+) -> Result<ExampleFiles, ArchiveError> {
+    let mut files = ExampleFiles::default();
+    for (i, input) in examples.inputs.iter().enumerate() {
+        let path = match i {
+            0 => config.example_path(day),
+            _ => config
+                .resolve(&config.input_dir)
+                .join(format!("test_day{day:02}_{}.txt", i + 1)),
+        };
+        if path.exists() && !overwrite {
+            files.kept.push(path);
+            continue;
+        }
+        fs::write(&path, input).map_err(|source| ArchiveError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        files.written.push(path);
+    }
+
+    if !examples.answers.is_empty() {
+        let path = config.examples_path();
+        let mut answers = Answers::load(&path).map_err(|e| ArchiveError::Answers(e.to_string()))?;
+        let mut changed = false;
+        let mut kept = false;
+        for (part, answer) in examples.answers.iter().enumerate() {
+            let part = part as u8 + 1;
+            match answers.get(day, part) {
+                Some(existing) if existing == answer => {}
+                Some(_) if !overwrite => kept = true,
+                _ => {
+                    answers.set(day, part, answer);
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            answers
+                .save(&path)
+                .map_err(|e| ArchiveError::Answers(e.to_string()))?;
+            files.written.push(path.clone());
+        }
+        if kept {
+            files.kept.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Renders the puzzle descriptions of a page as wrapped plain text.
+pub fn render_text(html: &str) -> String {
+    let tokens = tokenize(html);
+    let articles = articles(&tokens);
+
+    let mut out = String::new();
+    let mut paragraph = String::new();
+    let mut pre: Option<String> = None;
+
+    let end_paragraph = |out: &mut String, paragraph: &mut String| {
+        let text = paragraph.trim();
+        if !text.is_empty() {
+            out.push_str(&wrap(text, TEXT_WIDTH));
+            out.push_str("\n\n");
+        }
+        paragraph.clear();
+    };
+
+    for article in articles {
+        for &token in article {
+            match (token, pre.as_mut()) {
+                (Token::Text(text), Some(block)) => block.push_str(&decode(text)),
+                (Token::Close("pre"), Some(block)) => {
+                    for line in block.trim_end_matches('\n').lines() {
+                        out.push_str("    ");
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                    out.push('\n');
+                    pre = None;
+                }
+                (_, Some(_)) => {}
+                (Token::Text(text), None) => {
+                    let text = decode(text);
+                    if text.starts_with(char::is_whitespace) && !paragraph.ends_with(' ') {
+                        paragraph.push(' ');
+                    }
+                    paragraph.push_str(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+                    if text.ends_with(char::is_whitespace) && !text.trim().is_empty() {
+                        paragraph.push(' ');
+                    }
+                }
+                (Token::Open("pre"), None) => {
+                    end_paragraph(&mut out, &mut paragraph);
+                    pre = Some(String::new());
+                }
+                (Token::Open("li"), None) => {
+                    end_paragraph(&mut out, &mut paragraph);
+                    paragraph.push_str("- ");
+                }
+                (Token::Open(tag) | Token::Close(tag), None)
+                    if matches!(tag, "p" | "h2" | "ul" | "li" | "br") =>
+                {
+                    end_paragraph(&mut out, &mut paragraph);
+                }
+                _ => {}
+            }
+        }
+        end_paragraph(&mut out, &mut paragraph);
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+/// Extracts the `<pre><code>` blocks and the emphasized example answers.
+pub fn extract_examples(html: &str) -> Examples {
+    let tokens = tokenize(html);
+    let mut examples = Examples::default();
+
+    for article in articles(&tokens) {
+        let mut answer = None;
+        let mut block: Option<String> = None;
+
+        for (i, &token) in article.iter().enumerate() {
+            match (token, block.as_mut()) {
+                (Token::Open("pre"), _) => block = Some(String::new()),
+                (Token::Close("pre"), Some(text)) => {
+                    examples.inputs.push(text.clone());
+                    block = None;
+                }
+                (Token::Text(text), Some(block)) => block.push_str(&decode(text)),
+                _ => {}
+            }
+
+            // the answers are written as <code><em>11</em></code>, or the other way around
+            if let Some(
+                &[Token::Open(a), Token::Open(b), Token::Text(text), Token::Close(c), Token::Close(d)],
+            ) = article.get(i..i + 5)
+            {
+                let emphasized_code = (a, b) == ("code", "em") || (a, b) == ("em", "code");
+                if emphasized_code && (c, d) == (b, a) && block.is_none() {
+                    answer = Some(decode(text).trim().to_string());
+                }
+            }
+        }
+
+        // the last emphasized code of a part is the answer to its example
+        if let Some(answer) = answer {
+            examples.answers.push(answer);
+        }
+    }
+    examples
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        match tag.strip_prefix('/') {
+            Some(name) => tokens.push(Token::Close(name.trim())),
+            None if tag.starts_with('!') => {}
+            None => {
+                let name = &tag[..name_end];
+                // the contents of scripts and styles are no text
+                if name == "script" || name == "style" {
+                    let close = format!("</{name}>");
+                    rest = rest.find(&close).map_or("", |i| &rest[i + close.len()..]);
+                    continue;
+                }
+                tokens.push(Token::Open(name));
+            }
+        }
+    }
+    tokens
+}
+
+/// The tokens of each `<article>`, none when the page has no puzzle on it.
+fn articles<'a, 't>(tokens: &'t [Token<'a>]) -> Vec<&'t [Token<'a>]> {
+    let mut articles = vec![];
+    let mut start = None;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open("article") => start = Some(i + 1),
+            Token::Close("article") => {
+                if let Some(s) = start.take() {
+                    articles.push(&tokens[s..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    articles
+}
+
+fn decode(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| (&rest[1..end], end));
+        let replacement = entity.and_then(|(name, end)| {
+            let c = match name {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => name
+                    .strip_prefix("#x")
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| name.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match replacement {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn wrap(text: &str, width: usize) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines.join("\n")
+}
+
+fn read(path: &Path) -> Result<String, ArchiveError> {
+    fs::read_to_string(path).map_err(|source| ArchiveError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    NoPuzzle(PathBuf),
+    Answers(String),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::Io { path, source } => {
+                write!(f, "could not access {}: {source}", path.display())
+            }
+            ArchiveError::NoPuzzle(path) => {
+                write!(
+                    f,
+                    "{} does not contain a puzzle description",
+                    path.display()
+                )
+            }
+            ArchiveError::Answers(e) => write!(f, "could not store the example answers: {e}"),
+        }
+    }
+}
+
+impl Error for ArchiveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><script>var x = "<p>";</script></head><body>
+<main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2><p>Pair up the numbers &amp; measure
+how far apart they are.</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<ul><li>The first pair is <code>3</code>.</li></ul>
+<p>In the example above, this is <code><em>11</em></code>!</p>
+</article>
+<p>Your puzzle answer was <code>2769675</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>The score is <em><code>31</code></em>.</p></article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_extract_examples() {
+        let examples = extract_examples(PAGE);
+        assert_eq!(examples.inputs, vec!["3   4\n4   3\n"]);
+        assert_eq!(examples.answers, vec!["11", "31"]);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = render_text(PAGE);
+        assert_eq!(
+            text,
+            "--- Day 1: Historian Hysteria ---\n\n\
+             Pair up the numbers & measure how far apart they are.\n\n    \
+             3   4\n    4   3\n\n\
+             - The first pair is 3.\n\n\
+             In the example above, this is 11!\n\n\
+             --- Part Two ---\n\n\
+             The score is 31.\n"
+        );
+    }
+}
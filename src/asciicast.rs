@@ -0,0 +1,178 @@
+//! Recordings of simulations as asciinema casts.
+//!
+//! A day started with `--record out.cast` renders its [`Simulation`] after
+//! every `recording.steps_per_frame` steps and writes the frames to an
+//! asciicast v2 file as they are captured, which `asciinema play` or the web
+//! player can show. The delay between frames is `recording.frame_delay_ms`,
+//! and after `recording.max_frames` frames the recording skips to the final
+//! state. To watch a
+//! simulation right away, see [`crate::animation`].
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::simulation::Simulation;
+
+/// Moves the cursor home and clears the screen before each frame.
+const CLEAR: &str = "\x1b[H\x1b[2J";
+
+/// A cast being written, a frame at a time as it is captured.
+#[derive(Debug)]
+pub struct Recording<W: Write> {
+    out: W,
+    frame_delay: Duration,
+    /// The number of frames [`Recording::record`] captures before it skips
+    /// to the final state, 0 for no limit
+    max_frames: usize,
+    frames: usize,
+}
+
+impl Recording<BufWriter<File>> {
+    pub fn create(path: &Path, frame_delay: Duration, max_frames: usize) -> io::Result<Self> {
+        Ok(Self::new(
+            BufWriter::new(File::create(path)?),
+            frame_delay,
+            max_frames,
+        ))
+    }
+}
+
+impl<W: Write> Recording<W> {
+    pub fn new(out: W, frame_delay: Duration, max_frames: usize) -> Self {
+        Self {
+            out,
+            frame_delay,
+            max_frames,
+            frames: 0,
+        }
+    }
+
+    /// Writes a frame as an output event. The first frame also writes the
+    /// header line, so it sets the size of the terminal.
+    pub fn capture(&mut self, frame: &str) -> io::Result<()> {
+        if self.frames == 0 {
+            let width = frame.lines().map(|line| line.chars().count()).max();
+            let height = frame.lines().count();
+            writeln!(
+                self.out,
+                r#"{{"version": 2, "width": {}, "height": {height}}}"#,
+                width.unwrap_or(0)
+            )?;
+        }
+        // counted in milliseconds, which keeps the timestamps short
+        let time = (self.frame_delay.as_millis() * self.frames as u128) as f64 / 1000.0;
+        // a terminal needs the carriage return to start the next line at the left
+        let data = format!("{CLEAR}{}", frame.replace('\n', "\r\n"));
+        writeln!(self.out, "{}", serde_json::json!([time, "o", data]))?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Runs the simulation to the end, capturing a frame every `steps_per_frame`
+    /// steps until there are `max_frames` of them, and the first and last state.
+    pub fn record<S: Simulation>(
+        &mut self,
+        simulation: &mut S,
+        steps_per_frame: usize,
+    ) -> io::Result<()> {
+        self.capture(&simulation.render())?;
+        let mut steps = 0;
+        while simulation.step() {
+            steps += 1;
+            if steps % steps_per_frame.max(1) == 0 && !self.is_full() {
+                self.capture(&simulation.render())?;
+            }
+        }
+        self.capture(&simulation.render())
+    }
+
+    fn is_full(&self) -> bool {
+        self.max_frames != 0 && self.frames >= self.max_frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+
+    /// Flushes the cast, returning the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Records the simulation to `path` with the settings of the configuration,
+/// exiting with a message when the file can't be written.
+pub fn record_or_exit<S: Simulation>(config: &Config, path: &Path, simulation: &mut S) {
+    let frame_delay = Duration::from_millis(config.recording.frame_delay_ms);
+    let recorded = Recording::create(path, frame_delay, config.recording.max_frames).and_then(
+        |mut recording| {
+            recording.record(simulation, config.recording.steps_per_frame)?;
+            let frames = recording.len();
+            recording.finish().map(|_| frames)
+        },
+    );
+    match recorded {
+        Ok(frames) => println!("recorded {frames} frames to {}", path.display()),
+        Err(e) => {
+            eprintln!("error: could not write {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Countdown(u32);
+
+    impl Simulation for Countdown {
+        fn step(&mut self) -> bool {
+            if self.0 == 1 {
+                return false;
+            }
+            self.0 -= 1;
+            true
+        }
+
+        fn render(&self) -> String {
+            format!("{}\n{}", self.0, "#".repeat(self.0 as usize))
+        }
+    }
+
+    #[test]
+    fn test_write_cast() {
+        let mut recording = Recording::new(vec![], Duration::from_millis(500), 0);
+        recording.record(&mut Countdown(4), 2).unwrap();
+        let cast = String::from_utf8(recording.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+
+        // the start, after 2 steps and the end
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], r#"{"version": 2, "width": 4, "height": 2}"#);
+        assert_eq!(lines[1], r#"[0.0,"o","\u001b[H\u001b[2J4\r\n####"]"#);
+        assert_eq!(lines[3], r#"[1.0,"o","\u001b[H\u001b[2J1\r\n#"]"#);
+    }
+
+    #[test]
+    fn test_max_frames() {
+        let mut recording = Recording::new(vec![], Duration::from_millis(100), 2);
+        recording.record(&mut Countdown(6), 1).unwrap();
+        assert_eq!(recording.len(), 3);
+        let cast = String::from_utf8(recording.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+
+        // the start, the first step and then the end right away
+        assert_eq!(lines[0], r#"{"version": 2, "width": 6, "height": 2}"#);
+        assert_eq!(lines[2], r#"[0.1,"o","\u001b[H\u001b[2J5\r\n#####"]"#);
+        assert_eq!(lines[3], r#"[0.2,"o","\u001b[H\u001b[2J1\r\n#"]"#);
+    }
+}
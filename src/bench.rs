@@ -0,0 +1,335 @@
+//! Run time history of the day binaries.
+//!
+//! `aoc bench` runs every day binary a few times, one at a time, and appends
+//! the fastest wall time of each to the history file, keyed by the git
+//! commit and the date. `aoc bench report` charts the history of every day
+//! as an SVG and points out the commits after which a binary got slower.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+use crate::image::{palette, Rgb};
+use crate::tomlfile::{self, TomlError};
+
+/// Slowdowns smaller than this many seconds are noise, however large they
+/// are relative to the run time.
+const MIN_SLOWDOWN: f64 = 0.005;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct History {
+    #[serde(default)]
+    pub runs: Vec<BenchRun>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchRun {
+    /// Hash of the commit, with a `-dirty` suffix for uncommitted changes
+    pub commit: String,
+    pub date: String,
+    /// Wall time in seconds, by binary
+    pub seconds: BTreeMap<String, f64>,
+}
+
+/// A binary that got slower from one run of the history to the next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slowdown {
+    pub name: String,
+    /// Index of the slower run in the history
+    pub run: usize,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl History {
+    pub fn load(path: &Path) -> Result<Self, TomlError> {
+        tomlfile::load(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TomlError> {
+        tomlfile::save(self, path)
+    }
+
+    /// Appends a run, replacing an earlier run of the same commit and date.
+    pub fn add(&mut self, run: BenchRun) {
+        self.runs
+            .retain(|r| (&r.commit, &r.date) != (&run.commit, &run.date));
+        self.runs.push(run);
+    }
+
+    /// The names of the binaries in the history, by day.
+    pub fn binaries(&self) -> BTreeMap<u8, BTreeSet<&str>> {
+        let mut days: BTreeMap<u8, BTreeSet<&str>> = BTreeMap::new();
+        for name in self.runs.iter().flat_map(|run| run.seconds.keys()) {
+            if let Some(day) = day_of(name) {
+                days.entry(day).or_default().insert(name);
+            }
+        }
+        days
+    }
+
+    /// Every increase of more than `percent` between two consecutive runs of
+    /// a binary.
+    pub fn slowdowns(&self, percent: f64) -> Vec<Slowdown> {
+        let mut slowdowns = vec![];
+        let mut last: BTreeMap<&str, f64> = BTreeMap::new();
+        for (i, run) in self.runs.iter().enumerate() {
+            for (name, &after) in run.seconds.iter() {
+                if let Some(&before) = last.get(name.as_str()) {
+                    if after > before * (1.0 + percent / 100.0) && after - before > MIN_SLOWDOWN {
+                        slowdowns.push(Slowdown {
+                            name: name.clone(),
+                            run: i,
+                            before,
+                            after,
+                        });
+                    }
+                }
+                last.insert(name, after);
+            }
+        }
+        slowdowns
+    }
+
+    /// A line chart of the run times of the binaries of a day, with the
+    /// slowdowns circled in red.
+    pub fn trend_svg(&self, day: u8, slowdowns: &[Slowdown]) -> String {
+        const WIDTH: f64 = 720.0;
+        const HEIGHT: f64 = 360.0;
+        const LEFT: f64 = 70.0;
+        const RIGHT: f64 = 180.0;
+        const TOP: f64 = 40.0;
+        const BOTTOM: f64 = 50.0;
+
+        let names: Vec<&str> = self
+            .binaries()
+            .remove(&day)
+            .map(|names| names.into_iter().collect())
+            .unwrap_or_default();
+        let max = self
+            .runs
+            .iter()
+            .flat_map(|run| names.iter().filter_map(|&name| run.seconds.get(name)))
+            .fold(0.0, |max: f64, &seconds| max.max(seconds));
+        let max = if max > 0.0 { max * 1.1 } else { 1.0 };
+
+        let plot_width = WIDTH - LEFT - RIGHT;
+        let plot_height = HEIGHT - TOP - BOTTOM;
+        let x = |run: usize| match self.runs.len() {
+            0 | 1 => LEFT + plot_width / 2.0,
+            n => LEFT + plot_width * run as f64 / (n - 1) as f64,
+        };
+        let y = |seconds: f64| TOP + plot_height * (1.0 - seconds / max);
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="11">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<rect width="{WIDTH}" height="{HEIGHT}" fill="white"/>"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{LEFT}" y="24" font-size="15">day {day:02}, wall time per commit</text>"#
+        )
+        .unwrap();
+
+        // horizontal grid lines with the time they stand for
+        for tick in 0..=4 {
+            let seconds = max * tick as f64 / 4.0;
+            writeln!(
+                svg,
+                r##"<line x1="{LEFT}" y1="{0}" x2="{1}" y2="{0}" stroke="#dddddd"/><text x="{2}" y="{3}" text-anchor="end">{4}</text>"##,
+                y(seconds),
+                LEFT + plot_width,
+                LEFT - 6.0,
+                y(seconds) + 4.0,
+                format_seconds(seconds)
+            )
+            .unwrap();
+        }
+        for (i, run) in self.runs.iter().enumerate() {
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="middle"><title>{} {}</title>{}</text>"#,
+                x(i),
+                TOP + plot_height + 18.0,
+                run.commit,
+                run.date,
+                short_commit(&run.commit)
+            )
+            .unwrap();
+        }
+
+        for (index, &name) in names.iter().enumerate() {
+            let color = palette(index).hex();
+            let points: Vec<(f64, f64)> = self
+                .runs
+                .iter()
+                .enumerate()
+                .filter_map(|(i, run)| run.seconds.get(name).map(|&s| (x(i), y(s))))
+                .collect();
+            let polyline: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+            writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="2"/>"#,
+                polyline.join(" ")
+            )
+            .unwrap();
+            for (px, py) in points {
+                writeln!(svg, r#"<circle cx="{px}" cy="{py}" r="3" fill="{color}"/>"#).unwrap();
+            }
+
+            let legend_y = TOP + 16.0 * index as f64;
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="12" height="12" fill="{color}"/><text x="{}" y="{}">{name}</text>"#,
+                WIDTH - RIGHT + 16.0,
+                legend_y,
+                WIDTH - RIGHT + 34.0,
+                legend_y + 10.0
+            )
+            .unwrap();
+        }
+
+        for slowdown in slowdowns
+            .iter()
+            .filter(|s| names.contains(&s.name.as_str()))
+        {
+            writeln!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="7" fill="none" stroke="{}" stroke-width="2"><title>{} got slower: {} to {}</title></circle>"#,
+                x(slowdown.run),
+                y(slowdown.after),
+                Rgb::RED.hex(),
+                slowdown.name,
+                format_seconds(slowdown.before),
+                format_seconds(slowdown.after)
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// The day of a binary like `day05_refactored`.
+pub fn day_of(name: &str) -> Option<u8> {
+    name.strip_prefix("day")?.get(..2)?.parse().ok()
+}
+
+/// The abbreviated hash of a commit, keeping the `-dirty` suffix.
+pub fn short_commit(commit: &str) -> String {
+    let hash = commit.get(..7).unwrap_or(commit);
+    match commit.ends_with("-dirty") {
+        true => format!("{hash}-dirty"),
+        false => hash.to_string(),
+    }
+}
+
+pub fn format_seconds(seconds: f64) -> String {
+    match seconds {
+        s if s < 1.0 => format!("{:.1}ms", s * 1000.0),
+        s => format!("{s:.2}s"),
+    }
+}
+
+/// The commit checked out in `dir`, marked `-dirty` when there are
+/// uncommitted changes, or `unknown` outside of a git repository.
+pub fn git_commit(dir: &Path) -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    match git(&["rev-parse", "HEAD"]) {
+        Some(commit) => match git(&["status", "--porcelain", "--untracked-files=no"]) {
+            Some(changes) if !changes.is_empty() => format!("{commit}-dirty"),
+            _ => commit,
+        },
+        None => String::from("unknown"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(commit: &str, date: &str, seconds: &[(&str, f64)]) -> BenchRun {
+        BenchRun {
+            commit: commit.to_string(),
+            date: date.to_string(),
+            seconds: seconds.iter().map(|&(n, s)| (n.to_string(), s)).collect(),
+        }
+    }
+
+    fn history() -> History {
+        let mut history = History::default();
+        history.add(run(
+            "aaaaaaa1",
+            "2024-12-10",
+            &[("day06", 1.0), ("day09", 0.5)],
+        ));
+        history.add(run(
+            "bbbbbbb2",
+            "2024-12-11",
+            &[("day06", 1.1), ("day09faster", 0.01)],
+        ));
+        history.add(run(
+            "ccccccc3",
+            "2024-12-12",
+            &[("day06", 1.5), ("day09faster", 0.012)],
+        ));
+        history
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = history();
+        assert_eq!(
+            history.binaries()[&9],
+            BTreeSet::from(["day09", "day09faster"])
+        );
+
+        // a second run of a commit on the same day replaces the first
+        history.add(run("ccccccc3", "2024-12-12", &[("day06", 1.2)]));
+        assert_eq!(history.runs.len(), 3);
+        assert_eq!(history.runs[2].seconds["day06"], 1.2);
+
+        let text = toml::to_string(&history).unwrap();
+        let parsed: History = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.runs, history.runs);
+    }
+
+    #[test]
+    fn test_slowdowns() {
+        // day09faster got 20% slower as well, but by less than MIN_SLOWDOWN
+        let slowdowns = history().slowdowns(20.0);
+        assert_eq!(
+            slowdowns,
+            vec![Slowdown {
+                name: String::from("day06"),
+                run: 2,
+                before: 1.1,
+                after: 1.5,
+            }]
+        );
+
+        let svg = history().trend_svg(6, &slowdowns);
+        assert!(svg.contains(">ccccccc<"));
+        assert!(svg.contains("<title>day06 got slower: 1.10s to 1.50s</title>"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+    }
+}
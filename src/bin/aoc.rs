@@ -0,0 +1,487 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use advent_of_code_2024::archive;
+use advent_of_code_2024::bench::{self, BenchRun, History};
+use advent_of_code_2024::config::Config;
+use advent_of_code_2024::generate;
+use advent_of_code_2024::leaderboard;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::progress::{self, Answers, Progress};
+use advent_of_code_2024::runner::{self, Job, RunStatus};
+
+const USAGE: &str = "usage: aoc <command> [flags]
+
+commands:
+  all                    run every day binary, --jobs at the same time
+  bench                  time every day binary and add the times to the history
+  bench report <dir>     chart the history of every day as SVG and report slowdowns
+  check [day]            run the days on their example inputs and compare the
+                         answers to the example answers from the puzzles
+  status                 summarize the stars, unsolved parts and missing tests
+  verify <day> <part>    mark the last recorded answer of a part as correct
+  archive ingest <day> <page.html>
+                         store a saved puzzle page and extract its examples
+  archive show <day>     print an archived puzzle as text
+  archive extract <day>  write the examples of an archived puzzle to the inputs,
+                         replacing existing files with --force
+  lint <day> [file]      check an input against the grammar of the day
+  generate <day> [seed] [size]
+                         print a synthetic input for a day
+  leaderboard <file.json>
+                         analyze an exported private leaderboard, --json for JSON";
+
+fn main() {
+    let config = match Config::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let args: Vec<&str> = config.args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["all"] => all(&config),
+        ["bench"] => bench(&config),
+        ["bench", "report", dir] => bench_report(&config, dir),
+        ["check"] => check(&config, None),
+        ["check", day] => check(&config, Some(day)),
+        ["status"] => status(&config),
+        ["verify", day, part] => verify(&config, day, part),
+        ["archive", "ingest", day, file] => archive_ingest(&config, day, file),
+        ["archive", "show", day] => archive_show(&config, day),
+        ["archive", "extract", day] => archive_extract(&config, day),
+        ["lint", day] => lint(&config, day, None),
+        ["lint", day, file] => lint(&config, day, Some(file)),
+        ["generate", day] => generate(day, "0", "100"),
+        ["generate", day, seed] => generate(day, seed, "100"),
+        ["generate", day, seed, size] => generate(day, seed, size),
+        ["leaderboard", file] => leaderboard(&config, file),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// A binary solving a day, like `day05_refactored`.
+struct Implementation {
+    name: String,
+    has_tests: bool,
+}
+
+/// The day binaries in `src/bin`, by day.
+fn implementations() -> Result<BTreeMap<u8, Vec<Implementation>>, Box<dyn Error>> {
+    let bin_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/bin");
+    let mut days: BTreeMap<u8, Vec<Implementation>> = BTreeMap::new();
+
+    for entry in fs::read_dir(bin_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        let Some(day) = name
+            .strip_prefix("day")
+            .and_then(|rest| rest.get(..2))
+            .and_then(|digits| digits.parse::<u8>().ok())
+        else {
+            continue;
+        };
+        let has_tests = fs::read_to_string(&path)?.contains("#[cfg(test)]");
+        days.entry(day)
+            .or_default()
+            .push(Implementation { name, has_tests });
+    }
+
+    for implementations in days.values_mut() {
+        implementations.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    Ok(days)
+}
+
+/// The built day binaries, which live next to this one.
+fn jobs() -> Result<Vec<Job>, Box<dyn Error>> {
+    let exe = std::env::current_exe()?;
+    let bin_dir = exe.parent().ok_or("the runner has no directory")?;
+
+    let mut jobs = vec![];
+    for (day, implementations) in implementations()? {
+        for implementation in implementations {
+            let file_name = format!("{}{}", implementation.name, std::env::consts::EXE_SUFFIX);
+            jobs.push(Job {
+                day,
+                path: bin_dir.join(file_name),
+                name: implementation.name,
+            });
+        }
+    }
+    if let Some(missing) = jobs.iter().find(|job| !job.path.exists()) {
+        return Err(format!(
+            "{} is not built, run `cargo build --bins` first",
+            missing.name
+        )
+        .into());
+    }
+    Ok(jobs)
+}
+
+fn all(config: &Config) -> Result<(), Box<dyn Error>> {
+    let jobs = jobs()?;
+    let n_threads = match config.jobs {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let start = Instant::now();
+    let results = runner::run_all(
+        &jobs,
+        &config.flags,
+        n_threads,
+        Duration::from_secs(config.timeout),
+    );
+    print!("{}", runner::render_report(&results, start.elapsed()));
+
+    if config.solves_puzzle() {
+        for result in results.iter() {
+            for (&part, answer) in result.answers.iter() {
+                progress::record_as(config, result.day, part, answer, &result.name)?;
+            }
+        }
+    }
+
+    let failures = results.iter().filter(|r| r.status != RunStatus::Ok).count();
+    match failures {
+        0 => Ok(()),
+        _ => Err(format!("{failures} of {} binaries failed", results.len()).into()),
+    }
+}
+
+fn bench(config: &Config) -> Result<(), Box<dyn Error>> {
+    let jobs = jobs()?;
+    let timeout = Duration::from_secs(config.timeout);
+    let mut fastest: BTreeMap<String, Duration> = BTreeMap::new();
+    let mut failed = vec![];
+
+    // one binary at a time, so they don't compete for the cores
+    for round in 1..=config.bench.repeats.max(1) {
+        eprintln!("round {round} of {}", config.bench.repeats.max(1));
+        for result in runner::run_all(&jobs, &config.flags, 1, timeout) {
+            if result.status != RunStatus::Ok {
+                failed.push(result.name);
+                continue;
+            }
+            let time = fastest.entry(result.name).or_insert(result.wall_time);
+            *time = (*time).min(result.wall_time);
+        }
+    }
+    for name in failed.iter() {
+        fastest.remove(name);
+    }
+
+    let path = config.bench_history_path();
+    let mut history = History::load(&path)?;
+    let previous = history.runs.last().map(|run| run.seconds.clone());
+    let run = BenchRun {
+        commit: bench::git_commit(&config.resolve(Path::new("."))),
+        date: progress::timestamp(SystemTime::now())[..10].to_string(),
+        seconds: fastest
+            .iter()
+            .map(|(name, time)| (name.clone(), time.as_secs_f64()))
+            .collect(),
+    };
+
+    println!(
+        "commit {} on {}",
+        bench::short_commit(&run.commit),
+        run.date
+    );
+    for (name, &seconds) in run.seconds.iter() {
+        let change = previous
+            .as_ref()
+            .and_then(|previous| previous.get(name))
+            .map(|&before| format!("  {:+.0}%", (seconds / before - 1.0) * 100.0))
+            .unwrap_or_default();
+        println!("  {name:<18} {:>9}{change}", bench::format_seconds(seconds));
+    }
+    failed.sort();
+    failed.dedup();
+    if !failed.is_empty() {
+        println!("not timed, the binaries failed: {}", failed.join(", "));
+    }
+
+    history.add(run);
+    history.save(&path)?;
+    println!("added the times to {}", path.display());
+    Ok(())
+}
+
+fn bench_report(config: &Config, dir: &str) -> Result<(), Box<dyn Error>> {
+    let history = History::load(&config.bench_history_path())?;
+    if history.runs.is_empty() {
+        return Err("no benchmarks yet, run `aoc bench` first".into());
+    }
+    let dir = PathBuf::from(dir);
+    fs::create_dir_all(&dir)?;
+
+    let slowdowns = history.slowdowns(config.bench.slowdown_percent);
+    for &day in history.binaries().keys() {
+        let path = dir.join(format!("{}.svg", progress::day_key(day)));
+        fs::write(&path, history.trend_svg(day, &slowdowns))?;
+        println!("wrote {}", path.display());
+    }
+
+    match slowdowns.is_empty() {
+        true => println!(
+            "no binary got more than {}% slower",
+            config.bench.slowdown_percent
+        ),
+        false => println!("slowdowns of more than {}%:", config.bench.slowdown_percent),
+    }
+    for slowdown in slowdowns.iter() {
+        let run = &history.runs[slowdown.run];
+        println!(
+            "  {:<18} {} on {}: {} to {}",
+            slowdown.name,
+            bench::short_commit(&run.commit),
+            run.date,
+            bench::format_seconds(slowdown.before),
+            bench::format_seconds(slowdown.after)
+        );
+    }
+    Ok(())
+}
+
+fn check(config: &Config, day: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let only_day: Option<u8> = day.map(str::parse).transpose()?;
+    let examples = Answers::load(&config.examples_path())?;
+    let jobs = jobs()?;
+    let timeout = Duration::from_secs(config.timeout);
+
+    let mut checked = 0;
+    let mut mismatches = 0;
+    for day in 1..=25 {
+        if only_day.is_some_and(|only_day| only_day != day) {
+            continue;
+        }
+        let expected: Vec<(u8, &str)> = (1..=2)
+            .filter_map(|part| examples.get(day, part).map(|answer| (part, answer)))
+            .collect();
+        if expected.is_empty() {
+            continue;
+        }
+        let input = config.example_path(day);
+        if !input.exists() {
+            println!(" {day:02}  no example input {}", input.display());
+            continue;
+        }
+
+        let day_jobs: Vec<Job> = jobs.iter().filter(|job| job.day == day).cloned().collect();
+        let args = [String::from("--input"), input.display().to_string()];
+        for result in runner::run_all(&day_jobs, &args, day_jobs.len(), timeout) {
+            for &(part, answer) in expected.iter() {
+                checked += 1;
+                match result.answers.get(&part) {
+                    Some(actual) if actual == answer => {
+                        println!(" {day:02}  {:<18} part {part}  ok", result.name)
+                    }
+                    actual => {
+                        mismatches += 1;
+                        println!(
+                            " {day:02}  {:<18} part {part}  expected {answer}, got {}",
+                            result.name,
+                            actual.map_or("no answer", String::as_str)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    match (checked, mismatches) {
+        (0, _) => Err(format!(
+            "no example answers in {}, see `aoc archive extract`",
+            config.examples_path().display()
+        )
+        .into()),
+        (_, 0) => {
+            println!("all {checked} example answers match");
+            Ok(())
+        }
+        _ => Err(format!("{mismatches} of {checked} example answers differ").into()),
+    }
+}
+
+fn status(config: &Config) -> Result<(), Box<dyn Error>> {
+    let progress = Progress::load(&config.progress_path())?;
+    let days = implementations()?;
+
+    let mut unsolved = vec![];
+    let mut missing_tests = vec![];
+
+    println!("day  part 1  part 2  implementations");
+    for (&day, implementations) in days.iter() {
+        let mut parts = vec![];
+        for part in 1..=2 {
+            let mark = match progress.get(day, part) {
+                Some(p) if p.verified => "*",
+                Some(_) => "?",
+                None => ".",
+            };
+            if mark != "*" {
+                unsolved.push(format!("{} part {part}", progress::day_key(day)));
+            }
+            parts.push(mark);
+        }
+
+        if !implementations.iter().any(|i| i.has_tests) {
+            missing_tests.push(progress::day_key(day));
+        }
+
+        let names: Vec<&str> = implementations.iter().map(|i| i.name.as_str()).collect();
+        println!(
+            " {day:02}  {:<6}  {:<6}  {}",
+            parts[0],
+            parts[1],
+            names.join(", ")
+        );
+    }
+
+    println!();
+    println!("* verified, ? answered but not verified, . no answer");
+    println!();
+    println!("stars: {}/50", progress.stars());
+    println!("days started: {}/25", days.len());
+    println!("unsolved parts: {}", list_or_none(&unsolved));
+    println!("days without tests: {}", list_or_none(&missing_tests));
+
+    for &day in days.keys() {
+        for part in 1..=2 {
+            if let Some(p) = progress.get(day, part).filter(|p| p.verified) {
+                println!(
+                    "{} part {part} solved {} by {}",
+                    progress::day_key(day),
+                    p.first_solved.as_deref().unwrap_or("at an unknown time"),
+                    p.implementation
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn verify(config: &Config, day: &str, part: &str) -> Result<(), Box<dyn Error>> {
+    let day: u8 = day.parse()?;
+    let part: u8 = part.parse()?;
+    if !(1..=2).contains(&part) {
+        return Err("the part must be 1 or 2".into());
+    }
+
+    let mut progress = Progress::load(&config.progress_path())?;
+    let Some(entry) = progress.get(day, part).cloned() else {
+        return Err(format!(
+            "no answer recorded for {} part {part}",
+            progress::day_key(day)
+        )
+        .into());
+    };
+
+    let mut answers = Answers::load(&config.answers_path())?;
+    answers.set(day, part, &entry.answer);
+    answers.save(&config.answers_path())?;
+
+    progress.update(day, part, &entry.answer, &entry.implementation, &answers);
+    progress.save(&config.progress_path())?;
+
+    println!(
+        "{} part {part}: {} is now the verified answer",
+        progress::day_key(day),
+        entry.answer
+    );
+    Ok(())
+}
+
+fn archive_ingest(config: &Config, day: &str, file: &str) -> Result<(), Box<dyn Error>> {
+    let day: u8 = day.parse()?;
+    let path = archive::ingest(config, day, Path::new(file))?;
+    println!("stored the puzzle as {}", path.display());
+    archive_extract(config, &day.to_string())
+}
+
+fn archive_show(config: &Config, day: &str) -> Result<(), Box<dyn Error>> {
+    let html = archive::load(config, day.parse()?)?;
+    print!("{}", archive::render_text(&html));
+    Ok(())
+}
+
+fn archive_extract(config: &Config, day: &str) -> Result<(), Box<dyn Error>> {
+    let day: u8 = day.parse()?;
+    let examples = archive::extract_examples(&archive::load(config, day)?);
+    println!(
+        "found {} example inputs and the example answers {:?}",
+        examples.inputs.len(),
+        examples.answers
+    );
+    let files = archive::write_examples(config, day, &examples, config.force)?;
+    for path in files.written.iter() {
+        println!("wrote {}", path.display());
+    }
+    for path in files.kept.iter() {
+        println!("kept the existing {}", path.display());
+    }
+    if !files.kept.is_empty() {
+        println!("use --force to replace the existing files");
+    }
+    Ok(())
+}
+
+fn lint(config: &Config, day: &str, file: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let day: u8 = day.parse()?;
+    let validator = lint::validator(day).ok_or(format!("day {day} has no validator"))?;
+    let path = file.map_or_else(|| config.input_path(day), PathBuf::from);
+    let input = fs::read_to_string(&path)?;
+
+    let diagnostics = validator.check(&input);
+    for diagnostic in diagnostics.iter() {
+        let error = diagnostic.to_parse_error(&input);
+        println!("{}\n", error.in_file(path.display().to_string()));
+    }
+    if !diagnostics.is_empty() {
+        println!("expected {}", validator.grammar);
+        return Err(format!("found {} problems", diagnostics.len()).into());
+    }
+    println!("{} is a valid input for day {day}", path.display());
+    Ok(())
+}
+
+fn generate(day: &str, seed: &str, size: &str) -> Result<(), Box<dyn Error>> {
+    let day: u8 = day.parse()?;
+    let input = generate::generate(day, seed.parse()?, size.parse()?)
+        .ok_or(format!("day {day} has no generator"))?;
+    print!("{input}");
+    Ok(())
+}
+
+fn leaderboard(config: &Config, file: &str) -> Result<(), Box<dyn Error>> {
+    let json = fs::read_to_string(file)?;
+    let report = leaderboard::analyze(&leaderboard::parse(&json)?, config.year);
+    match config.json {
+        true => println!("{}", serde_json::to_string_pretty(&report)?),
+        false => print!("{}", leaderboard::render_text(&report)),
+    }
+    Ok(())
+}
+
+fn list_or_none(items: &[String]) -> String {
+    match items.is_empty() {
+        true => String::from("none"),
+        false => items.join(", "),
+    }
+}
@@ -1,4 +1,7 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::explain::Explain;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
 use std::{collections::HashMap, str};
 
 // Maybe the lists are only off by a small amount! To find out, pair up the numbers and measure how far apart they are. Pair up the smallest number in the left list with the smallest number in the right list, then the second-smallest left number with the second-smallest right number, and so on.
@@ -78,9 +81,23 @@ fn get_similarity_score(left: &[u32], right: &[u32]) -> u32 {
 }
 
 fn main() {
-    let data = file_reader::get_input("src/inputs/input_day01.txt");
+    let config = config::load();
+    let data = config.read_input(1);
+    lint::check_or_exit(1, &data);
 
     let (left, right) = get_lists(data);
+    let explain = Explain::new(&config, 1);
+
+    if explain.enabled() {
+        let mut first = left.clone();
+        let mut second = right.clone();
+        first.sort_unstable();
+        second.sort_unstable();
+        for (a, b) in first.into_iter().zip(second) {
+            let details = serde_json::json!({"left": a, "right": b, "distance": a.abs_diff(b)});
+            explain.step(1, "pair", details);
+        }
+    }
 
     let total_difference = match get_total_difference(&left, &right) {
         Ok(diff) => diff,
@@ -88,8 +105,10 @@ fn main() {
     };
 
     println!("The total difference is {total_difference}");
+    runner::answer(&config, 1, 1, total_difference);
 
     let similarity_score = get_similarity_score(&left, &right);
 
     println!("The similarity score is {similarity_score}");
+    runner::answer(&config, 1, 2, similarity_score);
 }
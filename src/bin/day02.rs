@@ -1,25 +1,35 @@
 use std::str::FromStr;
 
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::diagnostics::{self, ParseError};
+use advent_of_code_2024::explain::Explain;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
 
 #[derive(Debug)]
 struct ReportGrid {
     grid: Vec<Vec<i32>>,
 }
 
-#[derive(Debug)]
-struct ReportGridError {}
-
 impl FromStr for ReportGrid {
-    type Err = ReportGridError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut grid = vec![];
-        for line in s.lines() {
-            let report = line
-                .split_whitespace()
-                .filter_map(|x| x.parse().ok())
-                .collect();
+        for (i, line) in s.lines().enumerate() {
+            let mut report = vec![];
+            for (column, level) in diagnostics::words(line) {
+                let level = level.parse().map_err(|_| {
+                    ParseError::new(
+                        s,
+                        i + 1,
+                        column,
+                        format!("expected a level, found {level:?}"),
+                    )
+                    .with_width(level.chars().count())
+                })?;
+                report.push(level);
+            }
             grid.push(report);
         }
 
@@ -29,22 +39,28 @@ impl FromStr for ReportGrid {
 
 impl ReportGrid {
     fn check_report_safety(report: &[i32]) -> bool {
+        ReportGrid::failing_window(report).is_none()
+    }
+
+    /// The index of the first pair of levels that makes the report unsafe,
+    /// with the reason why.
+    fn failing_window(report: &[i32]) -> Option<(usize, &'static str)> {
         if report.len() < 2 {
-            return false;
+            return Some((0, "a report needs at least two levels"));
         }
 
         let mut increasing = None;
 
-        for window in report.windows(2) {
+        for (i, window) in report.windows(2).enumerate() {
             let diff = window[1] - window[0];
             if diff.abs() < 1 || diff.abs() > 3 {
-                return false;
+                return Some((i, "the levels differ by less than 1 or more than 3"));
             }
 
             match increasing {
                 Some(inc) => {
                     if (diff > 0) != inc {
-                        return false;
+                        return Some((i, "the levels change direction"));
                     }
                 }
                 None => {
@@ -52,7 +68,36 @@ impl ReportGrid {
                 }
             }
         }
-        true
+        None
+    }
+
+    fn explain(&self, explain: &Explain) {
+        for (i, report) in self.grid.iter().enumerate() {
+            let Some((window, reason)) = ReportGrid::failing_window(report) else {
+                continue;
+            };
+            let details = serde_json::json!({
+                "report": i + 1,
+                "levels": report,
+                "window": report.get(window..window + 2).unwrap_or(report),
+                "reason": reason,
+            });
+            explain.step(1, "unsafe report", details);
+
+            let removed = (0..report.len()).find(|&level| {
+                let mut damped_report = report.to_owned();
+                damped_report.remove(level);
+                ReportGrid::check_report_safety(&damped_report)
+            });
+            if let Some(level) = removed {
+                let details = serde_json::json!({
+                    "report": i + 1,
+                    "removed_level": report[level],
+                    "position": level,
+                });
+                explain.step(2, "dampened report", details);
+            }
+        }
     }
 
     fn problem_a(&self) -> usize {
@@ -91,13 +136,20 @@ impl ReportGrid {
 }
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day02.txt");
+    let config = config::load();
+    let input = config.read_input(2);
+    lint::check_or_exit(2, &input);
     // we need to check which lines are safe
     // we will iterate per line, and can call a recursive function or iterate over the line
     //
     // dbg!(&reports);
 
     let reports = ReportGrid::from_str(&input).unwrap();
+    let explain = Explain::new(&config, 2);
+    if explain.enabled() {
+        reports.explain(&explain);
+    }
+
     let n_safe_reports = reports.problem_a();
     let n_problem_damper_reports = reports.problem_b();
 
@@ -107,4 +159,7 @@ fn main() {
         "The total number of safe reports is {}",
         n_safe_reports + n_problem_damper_reports
     );
+
+    runner::answer(&config, 2, 1, n_safe_reports);
+    runner::answer(&config, 2, 2, n_safe_reports + n_problem_damper_reports);
 }
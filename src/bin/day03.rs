@@ -1,8 +1,11 @@
 use regex::Regex;
-use std::error::Error;
 use std::str::FromStr;
 
-use advent_of_code_2024::file_reader::get_input;
+use advent_of_code_2024::config;
+use advent_of_code_2024::diagnostics::ParseError;
+use advent_of_code_2024::explain::Explain;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
 // use regex::Regex;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -24,7 +27,7 @@ struct TobogganComputer {
 }
 
 impl FromStr for TobogganComputer {
-    type Err = Box<dyn Error>;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut instructions = vec![];
@@ -37,14 +40,29 @@ impl FromStr for TobogganComputer {
 
         let dont_regex = Regex::new(r"don't\(\)(.*?)do\(\)").unwrap();
 
-        let active_strings: Vec<&str> = dont_regex.split(s).collect();
-
-        for string in active_strings {
-            for capture in mul_regex.captures_iter(string) {
-                instructions.push(Instruction::Mul(
-                    capture["number1"].parse().unwrap(),
-                    capture["number2"].parse().unwrap(),
-                ));
+        // the active parts are kept as ranges, so errors can point into the input
+        let mut active_ranges = vec![];
+        let mut start = 0;
+        for inactive in dont_regex.find_iter(s) {
+            active_ranges.push(start..inactive.start());
+            start = inactive.end();
+        }
+        active_ranges.push(start..s.len());
+
+        for range in active_ranges {
+            for capture in mul_regex.captures_iter(&s[range.clone()]) {
+                let number = |name: &str| {
+                    let m = capture.name(name).unwrap();
+                    m.as_str().parse().map_err(|_| {
+                        ParseError::at_offset(
+                            s,
+                            range.start + m.start(),
+                            m.len(),
+                            "invalid mul operand",
+                        )
+                    })
+                };
+                instructions.push(Instruction::Mul(number("number1")?, number("number2")?));
             }
         }
         Ok(TobogganComputer { instructions })
@@ -61,7 +79,9 @@ impl TobogganComputer {
 }
 
 fn main() {
-    let input = get_input("src/inputs/input_day03.txt");
+    let config = config::load();
+    let input = config.read_input(3);
+    lint::check_or_exit(3, &input);
     let computer = TobogganComputer::from_str(&input).unwrap();
     // println!(
     //     "The found multiplication instructions are: {:?}",
@@ -69,11 +89,23 @@ fn main() {
     // );
 
     dbg!(&computer.instructions);
+    let explain = Explain::new(&config, 3);
+    if explain.enabled() {
+        for instruction in computer.instructions.iter() {
+            let Instruction::Mul(a, b) = instruction;
+            let details = serde_json::json!({"mul": [a, b], "product": instruction.execute()});
+            explain.step(2, "active mul", details);
+        }
+    }
     let result = computer.execute();
     println!(
         "The result coming from the computer for part a is: {}",
         result
-    )
+    );
+
+    // the don't() sections are already dropped while parsing,
+    // so this is the answer to part b
+    runner::answer(&config, 3, 2, result);
 }
 
 #[cfg(test)]
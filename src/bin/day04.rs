@@ -1,79 +1,51 @@
-use std::error::Error;
 use std::fmt;
 use std::str::FromStr;
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
 
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::diagnostics::ParseError;
+use advent_of_code_2024::direction::Direction;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::image::{self, Picture, Rgb};
+use advent_of_code_2024::lint;
+use advent_of_code_2024::repl::{self, Repl, ReplError};
+use advent_of_code_2024::runner;
 
 #[derive(Debug)]
 struct WordSearch {
     pub word: String,
-    grid: Vec<Vec<char>>,
-    period_grid: Vec<Vec<char>>,
+    /// Let the words wrap around the edges of the grid
+    pub wrap: bool,
+    grid: Grid<char>,
+    period_grid: Grid<char>,
 }
 
 impl FromStr for WordSearch {
-    type Err = Box<dyn Error>;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut grid = vec![];
-        for line in s.lines() {
-            let mut line_vec: Vec<char> = vec![];
-            for char in line.chars() {
-                line_vec.push(char);
-            }
-            grid.push(line_vec);
-        }
+        let grid: Grid<char> = Grid::parse(s, Some)?;
         Ok(WordSearch {
             word: String::from(""),
-            grid: grid.clone(),
-            period_grid: vec![vec!['.'; grid[0].len()]; grid.len()],
+            wrap: false,
+            period_grid: Grid::filled(grid.rows(), grid.cols(), '.'),
+            grid,
         })
     }
 }
 impl fmt::Display for WordSearch {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Grid:")?;
-        for row in &self.grid {
-            for &char in row {
-                write!(f, "{}", char)?;
-            }
-            writeln!(f)?;
-        }
+        write!(f, "{}", self.grid)?;
         writeln!(f, "Period Grid:")?;
-        for row in &self.period_grid {
-            for &char in row {
-                write!(f, "{}", char)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.period_grid)
     }
 }
 
-#[derive(Debug, EnumIter)]
-enum Direction {
-    N,
-    NE,
-    E,
-    SE,
-    S,
-    SW,
-    W,
-    NW,
-}
-
 impl WordSearch {
     fn problem_a(&mut self) -> u32 {
         let mut n_occurrences_word = 0;
-        let mut first_letter_coords: Vec<(usize, usize)> = vec![];
-        for (i, row) in self.grid.iter().enumerate() {
-            for (j, character) in row.iter().enumerate() {
-                if *character == self.word.chars().nth(0).unwrap() {
-                    first_letter_coords.push((i, j));
-                }
-            }
-        }
+        let first_letter = self.word.chars().nth(0).unwrap();
+        let first_letter_coords: Vec<(usize, usize)> =
+            self.grid.positions(|&c| c == first_letter).collect();
 
         for coord in first_letter_coords {
             for direction in Direction::iter() {
@@ -92,15 +64,8 @@ impl WordSearch {
         let mut n_occurrences_word = 0;
 
         let middle_letter = self.word.chars().nth(1).unwrap();
-        let mut middle_letter_coords: Vec<(usize, usize)> = vec![];
-
-        for (i, row) in self.grid.iter().enumerate() {
-            for (j, character) in row.iter().enumerate() {
-                if *character == middle_letter {
-                    middle_letter_coords.push((i, j));
-                }
-            }
-        }
+        let middle_letter_coords: Vec<(usize, usize)> =
+            self.grid.positions(|&c| c == middle_letter).collect();
 
         for coord in middle_letter_coords {
             if self.search_word_x(coord) {
@@ -120,24 +85,22 @@ impl WordSearch {
         let second_char = chars.next().unwrap();
         let third_char = chars.next().unwrap();
 
-        if self.grid[coords.0][coords.1] != second_char {
+        if self.grid[coords] != second_char {
             return false;
         }
 
-        if coords.0 >= self.grid.len() - 1 || coords.1 >= self.grid[0].len() - 1 {
+        // a cross in the corner of the grid is missing corners
+        let Some(corner_coordinates) = Direction::DIAGONAL
+            .iter()
+            .map(|&direction| self.step(coords, direction))
+            .collect::<Option<Vec<_>>>()
+        else {
             return false;
-        }
-
-        let corner_coordinates = [
-            (coords.0 - 1, coords.1 - 1),
-            (coords.0 - 1, coords.1 + 1),
-            (coords.0 + 1, coords.1 + 1),
-            (coords.0 + 1, coords.1 - 1),
-        ];
+        };
 
         let corner_characters: Vec<char> = corner_coordinates
             .iter()
-            .map(|(i, j)| self.grid[*i][*j])
+            .map(|&corner| self.grid[corner])
             .collect();
 
         // Count occurrences of the two characters using an iterator
@@ -162,72 +125,186 @@ impl WordSearch {
         }
 
         // if we reach here we can fill in the period grid
-        self.period_grid[coords.0][coords.1] = second_char;
-        for (coords, char) in corner_coordinates.iter().zip(corner_characters.iter()) {
-            self.period_grid[coords.0][coords.1] = *char;
+        self.period_grid[coords] = second_char;
+        for (&coords, &char) in corner_coordinates.iter().zip(corner_characters.iter()) {
+            self.period_grid[coords] = char;
         }
 
         true
     }
 
+    // the neighbor in `direction`, if the grid goes on or wraps around
+    fn step(&self, position: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        match self.wrap {
+            true => Some(self.grid.wrapping().step(position, direction)),
+            false => self.grid.step(position, direction),
+        }
+    }
+
     fn search_word_in_direction(&mut self, coords: (usize, usize), direction: Direction) -> bool {
         // this function will look if we match the word going right
-        let mut cursor = (coords.0 as isize, coords.1 as isize);
+        let mut cursor = Some(coords);
         let mut positions = vec![];
 
         for word_char in self.word.chars() {
             // see if the word character is matched
-            if cursor.0 < 0
-                || cursor.1 < 0
-                || cursor.0 >= self.grid.len() as isize
-                || cursor.1 >= self.grid[0].len() as isize
-            {
+            let Some(position) = cursor else {
                 return false;
-            }
+            };
 
-            if self.grid[cursor.0 as usize][cursor.1 as usize] != word_char {
+            if self.grid[position] != word_char {
                 return false;
             }
 
             // store the position
-            positions.push((cursor.0 as usize, cursor.1 as usize));
+            positions.push(position);
 
             // update the cursor
-            let (dx, dy) = match direction {
-                Direction::N => (-1, 0),
-                Direction::NE => (-1, 1),
-                Direction::E => (0, 1),
-                Direction::SE => (1, 1),
-                Direction::S => (1, 0),
-                Direction::SW => (1, -1),
-                Direction::W => (0, -1),
-                Direction::NW => (-1, -1),
-            };
-
-            cursor.0 += dx;
-            cursor.1 += dy;
+            cursor = self.step(position, direction);
         }
 
-        for (i, pos) in positions.iter().enumerate() {
-            self.period_grid[pos.0][pos.1] = self.word.chars().nth(i).unwrap();
+        for (i, &pos) in positions.iter().enumerate() {
+            self.period_grid[pos] = self.word.chars().nth(i).unwrap();
         }
         true
     }
 }
 
+impl WordSearch {
+    // the found letters, colored by their place in the word
+    fn picture(&self) -> Picture {
+        Picture::new(
+            self.period_grid.rows(),
+            self.period_grid.cols(),
+            |row, col| match self.period_grid[(row, col)] {
+                '.' => Rgb::WHITE,
+                c => self.word.find(c).map_or(Rgb::GRAY, image::palette),
+            },
+        )
+    }
+}
+
+impl Repl for WordSearch {
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, ReplError> {
+        match (name, args) {
+            ("show", []) => Ok(self.to_string()),
+            ("at", _) => {
+                let (row, col) = repl::parse_position(args)?;
+                match self.grid.get((row, col)) {
+                    Some(c) => Ok(format!("{c:?}, marked {:?}", self.period_grid[(row, col)])),
+                    None => Err(ReplError::Usage("position is outside of the grid")),
+                }
+            }
+            ("word", [word]) => {
+                self.word = word.to_string();
+                Ok(String::new())
+            }
+            ("wrap", [setting @ ("on" | "off")]) => {
+                self.wrap = *setting == "on";
+                Ok(String::new())
+            }
+            ("clear", []) => {
+                self.period_grid = Grid::filled(self.grid.rows(), self.grid.cols(), '.');
+                Ok(String::new())
+            }
+            ("run", ["part1"]) => {
+                self.word = String::from("XMAS");
+                Ok(format!(
+                    "The solution for question a is : {}",
+                    self.problem_a()
+                ))
+            }
+            ("run", ["part2"]) => {
+                self.word = String::from("MAS");
+                let solution = self
+                    .problem_b()
+                    .map_err(|_| ReplError::Usage("word must be 3 characters"))?;
+                Ok(format!("The solution for question b is : {solution}"))
+            }
+            ("show" | "word" | "wrap" | "clear" | "run", _) => {
+                Err(ReplError::Usage("wrong arguments, type help for usage"))
+            }
+            _ => Err(ReplError::UnknownCommand(name.to_string())),
+        }
+    }
+
+    fn help(&self) -> &[&str] {
+        &[
+            "show            print the grid and the found letters",
+            "at <row> <col>  show a letter",
+            "word <word>     set the word to search for",
+            "wrap on|off     let the words wrap around the edges",
+            "clear           forget the found letters",
+            "run part1|part2",
+        ]
+    }
+
+    fn prompt(&self) -> String {
+        format!("day04 {}> ", self.word)
+    }
+}
+
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day04.txt");
+    let config = config::load();
+    let input = config.read_input(4);
+    lint::check_or_exit(4, &input);
     let mut wordsearch_a = WordSearch::from_str(&input).unwrap();
     let mut wordsearch_b = WordSearch::from_str(&input).unwrap();
 
+    if config.repl {
+        repl::run(&mut wordsearch_a);
+        return;
+    }
+
     wordsearch_a.word = String::from("XMAS");
     wordsearch_b.word = String::from("MAS");
+    wordsearch_a.wrap = config.day04.wrap;
+    wordsearch_b.wrap = config.day04.wrap;
 
     let sol_problem_a = wordsearch_a.problem_a();
     println!("{}", wordsearch_a);
     println!("The solution for question a is : {sol_problem_a}");
+    runner::answer(&config, 4, 1, sol_problem_a);
+    if let Some(path) = &config.export {
+        image::export_or_exit(&config, path, &wordsearch_a.picture());
+    }
 
     let sol_prolem_b = wordsearch_b.problem_b();
-    println!("The solution for question b is : {}", sol_prolem_b.unwrap());
+    let sol_prolem_b = sol_prolem_b.unwrap();
+    println!("The solution for question b is : {sol_prolem_b}");
+    runner::answer(&config, 4, 2, sol_prolem_b);
     // println!("{}", wordsearch_b);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::snapshot::assert_snapshot;
+
+    const EXAMPLE: &str = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\n\
+                           XXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX\n";
+
+    #[test]
+    fn test_example() {
+        let mut wordsearch = WordSearch::from_str(EXAMPLE).unwrap();
+        wordsearch.word = String::from("XMAS");
+        assert_eq!(wordsearch.problem_a(), 18);
+        assert_snapshot(4, "xmas", &wordsearch.to_string());
+
+        // crosses touching the border of the grid are skipped, not a panic
+        let mut wordsearch = WordSearch::from_str(EXAMPLE).unwrap();
+        wordsearch.word = String::from("MAS");
+        assert_eq!(wordsearch.problem_b(), Ok(9));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let mut wordsearch = WordSearch::from_str("ASXM\n....\n").unwrap();
+        wordsearch.word = String::from("XMAS");
+        assert_eq!(wordsearch.problem_a(), 0);
+
+        // east from the X, across the right edge
+        wordsearch.wrap = true;
+        assert_eq!(wordsearch.problem_a(), 1);
+    }
+}
@@ -1,71 +1,55 @@
-use std::char;
-
-use advent_of_code_2024::file_reader;
-use grid::*;
-
-const DIRECTIONS: [(isize, isize); 8] = [
-    (-1, 0),  // N
-    (-1, 1),  // NE
-    (0, 1),   // E
-    (1, 1),   // SE
-    (1, 0),   // S
-    (1, -1),  // SW
-    (0, -1),  // W
-    (-1, -1), // NW
-];
+use advent_of_code_2024::config;
+use advent_of_code_2024::direction::Direction;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::point::Point;
+use advent_of_code_2024::runner;
 
 fn main() {
-    let puzzle_input = file_reader::get_input("src/inputs/input_day04.txt");
-    let grid_len: usize = puzzle_input.lines().next().unwrap().chars().count();
+    let config = config::load();
+    let puzzle_input = config.read_input(4);
+    lint::check_or_exit(4, &puzzle_input);
 
-    let grid: Grid<char> = Grid::from_vec(
-        puzzle_input.lines().flat_map(|l| l.chars()).collect(),
-        grid_len,
-    );
+    let grid: Grid<char> = Grid::parse(&puzzle_input, Some).unwrap();
 
     let search_word: &str = "XMAS";
-    let first_char = search_word.chars().next().unwrap();
-
-    let problem_a: u32 = grid
-        .indexed_iter()
-        .filter(|&(_, &c)| c == first_char)
-        .map(|((row, col), _)| search_in_directions(&grid, (row, col), search_word))
-        .sum();
+    let problem_a = count_words(&grid, search_word);
 
     println!("I found {problem_a} occurrences of the word {search_word}");
+    runner::answer(&config, 4, 1, problem_a);
 
-    let problem_b: usize = grid
-        .indexed_iter()
-        .filter(|&(_, &c)| c == 'A')
-        .filter(|&(index, _)| search_x_mas(&grid, index))
-        .count();
+    let problem_b = count_x_mas(&grid);
 
     println!("I found {problem_b} occurrences of the word MAS");
+    runner::answer(&config, 4, 2, problem_b);
+}
+
+fn count_words(grid: &Grid<char>, word: &str) -> u32 {
+    let first_char = word.chars().next().unwrap();
+    grid.positions(|&c| c == first_char)
+        .map(|start| search_in_directions(grid, Point::from(start), word))
+        .sum()
 }
 
-fn search_in_directions(grid: &Grid<char>, start: (usize, usize), word: &str) -> u32 {
+fn count_x_mas(grid: &Grid<char>) -> usize {
+    grid.positions(|&c| c == 'A')
+        .filter(|&index| search_x_mas(grid, Point::from(index)))
+        .count()
+}
+
+fn search_in_directions(grid: &Grid<char>, start: Point, word: &str) -> u32 {
     let mut matches_found = 0;
 
-    for &(dr, dc) in &DIRECTIONS {
+    for direction in Direction::iter() {
         let mut cursor = start;
         let mut match_found = true;
 
         for char in word.chars() {
-            match grid.get(cursor.0, cursor.1) {
-                None => {
-                    match_found = false;
-                    break;
-                }
-                Some(&grid_char) => {
-                    if grid_char != char {
-                        match_found = false;
-                        break;
-                    }
-                }
+            if grid.get_point(cursor) != Some(&char) {
+                match_found = false;
+                break;
             }
-
-            cursor.0 = (cursor.0 as isize + dr) as usize;
-            cursor.1 = (cursor.1 as isize + dc) as usize;
+            cursor = cursor + direction;
         }
         if match_found {
             matches_found += 1;
@@ -75,37 +59,33 @@ fn search_in_directions(grid: &Grid<char>, start: (usize, usize), word: &str) ->
     matches_found
 }
 
-fn search_x_mas(grid: &Grid<char>, index: (usize, usize)) -> bool {
-    if grid.get(index.0, index.1) != Some(&'A') {
+fn search_x_mas(grid: &Grid<char>, center: Point) -> bool {
+    if grid.get_point(center) != Some(&'A') {
         return false;
     }
 
-    // check if the characters to the bottem left
-    // and top right are m and s
+    // check if both diagonals through the A spell MAS,
+    // forward or backward
+    [Direction::NE, Direction::SE].into_iter().all(|direction| {
+        let ends = (
+            grid.get_point(center + direction),
+            grid.get_point(center + direction.reverse()),
+        );
+        matches!(ends, (Some('M'), Some('S')) | (Some('S'), Some('M')))
+    })
+}
 
-    let diag_1: Vec<Option<&char>> = vec![
-        grid.get((index.0 as isize - 1) as usize, index.1 + 1),
-        grid.get(index.0 + 1, (index.1 as isize - 1) as usize),
-    ];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let diag_2: Vec<Option<&char>> = vec![
-        grid.get(index.0 + 1, index.1 + 1),
-        grid.get(
-            (index.0 as isize - 1) as usize,
-            (index.1 as isize - 1) as usize,
-        ),
-    ];
+    const EXAMPLE: &str = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\n\
+                           XXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX\n";
 
-    //check if both diagonals spell MAS
-    // forward or backward
-
-    if diag_1 == vec![Some(&'M'), Some(&'S')]
-        || diag_1 == vec![Some(&'S'), Some(&'M')]
-        || diag_2 == vec![Some(&'M'), Some(&'S')]
-        || diag_2 == vec![Some(&'S'), Some(&'M')]
-    {
-        return true;
+    #[test]
+    fn test_example() {
+        let grid: Grid<char> = Grid::parse(EXAMPLE, Some).unwrap();
+        assert_eq!(count_words(&grid, "XMAS"), 18);
+        assert_eq!(count_x_mas(&grid), 9);
     }
-
-    false
 }
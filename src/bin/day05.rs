@@ -1,8 +1,14 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::counters;
+use advent_of_code_2024::explain::Explain;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
 use std::collections::HashMap;
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day05.txt");
+    let config = config::load();
+    let input = config.read_input(5);
+    lint::check_or_exit(5, &input);
 
     // the ordering page rules and the pages to produce
     // are separated by an empty line
@@ -33,10 +39,11 @@ fn main() {
         .collect();
 
     // split the sequences into correct and incorrect ones
-    let (correct_page_sequences, incorrect_page_sequences): (Vec<_>, Vec<_>) = page_sequences
-        .clone()
-        .into_iter()
-        .partition(|sequence| check_page_sequence(sequence, &comes_before, &comes_after));
+    let mut rule_checks = 0;
+    let (correct_page_sequences, incorrect_page_sequences): (Vec<_>, Vec<_>) =
+        page_sequences.clone().into_iter().partition(|sequence| {
+            check_page_sequence(sequence, &comes_before, &comes_after, &mut rule_checks)
+        });
 
     println!(
         "Out of the {} sequences of pages, {} were correct and {} were incorrect",
@@ -47,12 +54,39 @@ fn main() {
 
     let answer_a = sum_middle_page_numbers(&correct_page_sequences);
     println!("The sum of the middle page numbers is {answer_a}");
+    counters::add("rule checks", rule_checks);
+    runner::answer(&config, 5, 1, answer_a);
 
+    let mut rule_checks = 0;
+    let mut candidate_pages = 0;
     let corrected_page_sequences: Vec<Vec<u32>> = incorrect_page_sequences
         .iter()
-        .map(|sequence| correct_page_sequence(sequence, &comes_before, &comes_after))
+        .map(|sequence| {
+            correct_page_sequence(
+                sequence,
+                &comes_before,
+                &comes_after,
+                &mut rule_checks,
+                &mut candidate_pages,
+            )
+        })
         .collect();
 
+    let explain = Explain::new(&config, 5);
+    if explain.enabled() {
+        for (before, after) in incorrect_page_sequences
+            .iter()
+            .zip(corrected_page_sequences.iter())
+        {
+            let details = serde_json::json!({
+                "update": before,
+                "corrected": after,
+                "middle": after[after.len() / 2],
+            });
+            explain.step(2, "corrected update", details);
+        }
+    }
+
     println!(
         "We just created {} corrected page sequences, the length of the incorrect vector is now {}",
         corrected_page_sequences.len(),
@@ -63,7 +97,7 @@ fn main() {
 
     let all_corrected_ok: bool = corrected_page_sequences
         .iter()
-        .all(|s| check_page_sequence(s, &comes_before, &comes_after));
+        .all(|s| check_page_sequence(s, &comes_before, &comes_after, &mut rule_checks));
 
     match all_corrected_ok {
         true => println!("according to us, they are all correct"),
@@ -72,6 +106,9 @@ fn main() {
 
     let answer_b = sum_middle_page_numbers(&corrected_page_sequences);
     println!("The answer to part b is {answer_b}");
+    counters::add("rule checks", rule_checks);
+    counters::add("candidate pages", candidate_pages);
+    runner::answer(&config, 5, 2, answer_b);
 }
 
 fn sum_middle_page_numbers(page_sequences: &[Vec<u32>]) -> u32 {
@@ -82,8 +119,10 @@ fn check_page_sequence(
     sequence: &[u32],
     before_rules: &HashMap<u32, Vec<u32>>,
     after_rules: &HashMap<u32, Vec<u32>>,
+    rule_checks: &mut u64,
 ) -> bool {
     for (i, page) in sequence.iter().enumerate() {
+        *rule_checks += 1;
         if let Some(after_pages) = after_rules.get(page) {
             if sequence[..i].iter().any(|x| after_pages.contains(x)) {
                 return false;
@@ -103,12 +142,14 @@ fn correct_page_sequence(
     sequence: &[u32],
     before_rules: &HashMap<u32, Vec<u32>>,
     after_rules: &HashMap<u32, Vec<u32>>,
+    rule_checks: &mut u64,
+    candidate_pages: &mut u64,
 ) -> Vec<u32> {
     let mut old_sequence = sequence.to_vec();
     let mut corrected_sequence: Vec<u32> = vec![];
 
     while corrected_sequence.len() < sequence.len()
-        || !check_page_sequence(&corrected_sequence, before_rules, after_rules)
+        || !check_page_sequence(&corrected_sequence, before_rules, after_rules, rule_checks)
     {
         // loop through the sequence, and see if one of the pages
         // has zero of the other pages coming after it.
@@ -117,6 +158,7 @@ fn correct_page_sequence(
         let mut next_idx: Option<usize> = None;
 
         for (i, page) in old_sequence.iter().enumerate() {
+            *candidate_pages += 1;
             let mut other_pages = old_sequence.to_vec();
             other_pages.remove(i);
             let empty_vec = vec![];
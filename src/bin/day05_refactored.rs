@@ -1,10 +1,18 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::counters;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::repl::{self, Repl, ReplError};
+use advent_of_code_2024::runner;
+use std::cell::Cell;
 use std::collections::HashMap;
 
 #[derive(Debug)]
 struct PageRules {
     comes_after: HashMap<u32, Vec<u32>>,
     comes_before: HashMap<u32, Vec<u32>>,
+    // the work done so far, taken for the counters of each part
+    rule_checks: Cell<u64>,
+    candidate_pages: Cell<u64>,
 }
 
 impl PageRules {
@@ -12,6 +20,8 @@ impl PageRules {
         Self {
             comes_after: HashMap::new(),
             comes_before: HashMap::new(),
+            rule_checks: Cell::new(0),
+            candidate_pages: Cell::new(0),
         }
     }
 
@@ -22,6 +32,7 @@ impl PageRules {
 
     fn check_sequence(&self, sequence: &[u32]) -> bool {
         for (i, &page) in sequence.iter().enumerate() {
+            self.rule_checks.set(self.rule_checks.get() + 1);
             if let Some(after_pages) = self.comes_after.get(&page) {
                 if sequence[..i].iter().any(|x| after_pages.contains(x)) {
                     return false;
@@ -37,7 +48,9 @@ impl PageRules {
         true
     }
 
-    fn correct_sequence(&self, sequence: &[u32]) -> Vec<u32> {
+    /// The pages of `sequence` in an order that follows the rules, or `None`
+    /// when the rules between its pages go around in a cycle.
+    fn correct_sequence(&self, sequence: &[u32]) -> Option<Vec<u32>> {
         let mut old_sequence = sequence.to_vec();
         let mut corrected_sequence: Vec<u32> = vec![];
 
@@ -46,6 +59,7 @@ impl PageRules {
             let mut next_idx: Option<usize> = None;
 
             for (i, &page) in old_sequence.iter().enumerate() {
+                self.candidate_pages.set(self.candidate_pages.get() + 1);
                 let mut other_pages = old_sequence.clone();
                 other_pages.remove(i);
                 let empty_vec = &vec![];
@@ -56,17 +70,19 @@ impl PageRules {
                 }
             }
 
-            if let Some(index) = next_idx {
-                corrected_sequence.push(old_sequence[index]);
-                old_sequence.remove(index);
-            }
+            // every page left has to come after another one
+            let index = next_idx?;
+            corrected_sequence.push(old_sequence[index]);
+            old_sequence.remove(index);
         }
-        corrected_sequence
+        Some(corrected_sequence)
     }
 }
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day05.txt");
+    let config = config::load();
+    let input = config.read_input(5);
+    lint::check_or_exit(5, &input);
 
     let (ordering_page_rules, pages_to_produce) = input.split_once("\n\n").unwrap();
 
@@ -90,6 +106,14 @@ fn main() {
         })
         .collect();
 
+    if config.repl {
+        repl::run(&mut PrintQueue {
+            rules: page_rules,
+            updates: page_sequences,
+        });
+        return;
+    }
+
     let (correct_page_sequences, incorrect_page_sequences): (Vec<_>, Vec<_>) = page_sequences
         .clone()
         .into_iter()
@@ -104,10 +128,16 @@ fn main() {
 
     let answer_a = sum_middle_page_numbers(&correct_page_sequences);
     println!("The sum of the middle page numbers is {answer_a}");
+    counters::add("rule checks", page_rules.rule_checks.take());
+    runner::answer(&config, 5, 1, answer_a);
 
     let corrected_page_sequences: Vec<Vec<u32>> = incorrect_page_sequences
         .iter()
-        .map(|sequence| page_rules.correct_sequence(sequence))
+        .map(|sequence| {
+            page_rules
+                .correct_sequence(sequence)
+                .expect("no ordering satisfies the rules")
+        })
         .collect();
 
     println!(
@@ -129,8 +159,102 @@ fn main() {
 
     let answer_b = sum_middle_page_numbers(&corrected_page_sequences);
     println!("The answer to part b is {answer_b}");
+    counters::add("rule checks", page_rules.rule_checks.take());
+    counters::add("candidate pages", page_rules.candidate_pages.take());
+    runner::answer(&config, 5, 2, answer_b);
 }
 
 fn sum_middle_page_numbers(page_sequences: &[Vec<u32>]) -> u32 {
     page_sequences.iter().map(|x| x[x.len() / 2]).sum()
 }
+
+/// The parsed puzzle, explored with `--repl`.
+struct PrintQueue {
+    rules: PageRules,
+    updates: Vec<Vec<u32>>,
+}
+
+impl Repl for PrintQueue {
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, ReplError> {
+        match (name, args) {
+            ("check", [pages]) => {
+                let sequence: Vec<u32> = repl::parse_list(pages)?;
+                if self.rules.check_sequence(&sequence) {
+                    return Ok(String::from("correct"));
+                }
+                match self.rules.correct_sequence(&sequence) {
+                    Some(corrected) => Ok(format!("incorrect, should be {corrected:?}")),
+                    None => Ok(String::from("incorrect, no ordering satisfies the rules")),
+                }
+            }
+            ("rules", [page]) => {
+                let page: u32 = repl::parse_arg(page)?;
+                let empty_vec = vec![];
+                Ok(format!(
+                    "comes after {:?}\ncomes before {:?}",
+                    self.rules.comes_before.get(&page).unwrap_or(&empty_vec),
+                    self.rules.comes_after.get(&page).unwrap_or(&empty_vec)
+                ))
+            }
+            ("updates", []) => Ok(self
+                .updates
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| {
+                    let status = match self.rules.check_sequence(sequence) {
+                        true => "correct",
+                        false => "incorrect",
+                    };
+                    format!("{i:4}: {sequence:?} {status}")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            ("run", ["part1"]) => {
+                let correct: Vec<Vec<u32>> = self
+                    .updates
+                    .iter()
+                    .filter(|sequence| self.rules.check_sequence(sequence))
+                    .cloned()
+                    .collect();
+                Ok(format!(
+                    "The sum of the middle page numbers is {}",
+                    sum_middle_page_numbers(&correct)
+                ))
+            }
+            ("run", ["part2"]) => {
+                let corrected: Option<Vec<Vec<u32>>> = self
+                    .updates
+                    .iter()
+                    .filter(|sequence| !self.rules.check_sequence(sequence))
+                    .map(|sequence| self.rules.correct_sequence(sequence))
+                    .collect();
+                match corrected {
+                    Some(corrected) => Ok(format!(
+                        "The answer to part b is {}",
+                        sum_middle_page_numbers(&corrected)
+                    )),
+                    None => Ok(String::from(
+                        "no ordering satisfies the rules for one of the updates",
+                    )),
+                }
+            }
+            ("check" | "rules" | "updates" | "run", _) => {
+                Err(ReplError::Usage("wrong arguments, type help for usage"))
+            }
+            _ => Err(ReplError::UnknownCommand(name.to_string())),
+        }
+    }
+
+    fn help(&self) -> &[&str] {
+        &[
+            "check <pages>   check an update like 75,47,61 and correct it",
+            "rules <page>    show the pages that must come before and after",
+            "updates         list the updates of the input",
+            "run part1|part2",
+        ]
+    }
+
+    fn prompt(&self) -> String {
+        String::from("day05> ")
+    }
+}
@@ -1,50 +1,88 @@
 use std::collections::HashSet;
-use std::error::Error;
-use std::fmt;
 use std::str::FromStr;
 
-use advent_of_code_2024::file_reader;
-use grid::*;
+use advent_of_code_2024::animation;
+use advent_of_code_2024::asciicast;
+use advent_of_code_2024::config;
+use advent_of_code_2024::counters;
+use advent_of_code_2024::diagnostics::ParseError;
+use advent_of_code_2024::direction::Direction;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::image::{self, Picture, Rgb};
+use advent_of_code_2024::lint;
+use advent_of_code_2024::repl::{self, Repl, ReplError};
+use advent_of_code_2024::runner;
+use advent_of_code_2024::simulation::Simulation;
 
 fn main() {
-    let input = file_reader::get_input(&format!(
-        "{}/src/inputs/input_day06.txt",
-        env!("CARGO_MANIFEST_DIR")
-    ));
+    let config = config::load();
+    let input = config.read_input(6);
+    lint::check_or_exit(6, &input);
 
     let mut suitmaplab = SuitLabMap::from_str(&input).unwrap();
 
+    if config.repl {
+        repl::run(&mut suitmaplab);
+        return;
+    }
+    if let Some(path) = &config.record {
+        asciicast::record_or_exit(&config, path, &mut suitmaplab);
+        return;
+    }
+    if config.animate {
+        animation::run(&config, &mut suitmaplab);
+        return;
+    }
+
     // fill up the history
+    let mut guard_steps = 0;
     loop {
         let finished = suitmaplab.step();
+        guard_steps += 1;
         if finished == GuardStatus::Finished {
             break;
         }
     }
+    counters::add("guard steps", guard_steps);
 
     let answer_a = suitmaplab.get_n_guard_positions();
     println!("the guard has visited {answer_a} unique positions");
+    runner::answer(&config, 6, 1, answer_a);
+    if let Some(path) = &config.export {
+        image::export_or_exit(&config, path, &suitmaplab.picture());
+    }
 
-    // for part b we need to obstruct the guard
-    // we loop through all unique positions in the "normal" history, except the first one
-    // and place an extra obstacle there.
-    // Then we step and see if we find a loop
+    let start = SuitLabMap::from_str(&input).unwrap();
+    let loop_obstacle_locations = find_loop_obstacles(&start, &suitmaplab);
 
-    let mut obstacle_placement_locations = suitmaplab
+    println!(
+        "Found {} unique obstacle placement locations",
+        loop_obstacle_locations.len()
+    );
+    runner::answer(&config, 6, 2, loop_obstacle_locations.len());
+}
+
+// for part b we need to obstruct the guard
+// we loop through all unique positions in the "normal" history, except the first one
+// and place an extra obstacle there.
+// Then we step and see if we find a loop
+fn find_loop_obstacles(start: &SuitLabMap, walked: &SuitLabMap) -> Vec<(usize, usize)> {
+    let mut obstacle_placement_locations = walked
         .guard_history
-        .clone()
         .iter()
         .map(|&(row, col, _)| (row, col))
         .collect::<HashSet<_>>();
 
-    obstacle_placement_locations.remove(&suitmaplab.guard_starting_position);
+    obstacle_placement_locations.remove(&start.guard_starting_position);
     let mut loop_obstacle_locations: Vec<(usize, usize)> = vec![];
+    let mut guard_steps = 0;
 
     for (row, col) in obstacle_placement_locations.iter() {
-        let mut fresh_suitmaplab = SuitLabMap::from_str(&input).unwrap();
+        let mut fresh_suitmaplab = start.clone();
         fresh_suitmaplab.add_obstacle(*row, *col);
         loop {
             let step_result = fresh_suitmaplab.step();
+            guard_steps += 1;
             match step_result {
                 GuardStatus::Loop => {
                     loop_obstacle_locations.push((*row, *col));
@@ -57,11 +95,8 @@ fn main() {
             }
         }
     }
-
-    println!(
-        "Found {} unique obstacle placement locations",
-        loop_obstacle_locations.len()
-    );
+    counters::add("guard steps", guard_steps);
+    loop_obstacle_locations
 }
 
 #[derive(Debug, PartialEq)]
@@ -71,28 +106,25 @@ enum GuardStatus {
     Normal,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-enum Orientation {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct SuitLabMap {
     guard_starting_position: (usize, usize),
     guard_position: (usize, usize),
-    guard_orientation: Orientation,
-    map: grid::Grid<char>,
+    guard_orientation: Direction,
+    map: Grid<char>,
     // guard_history: Vec<(usize, usize)>,
-    guard_history: HashSet<(usize, usize, Orientation)>,
+    guard_history: HashSet<(usize, usize, Direction)>,
 }
 
 impl SuitLabMap {
-    fn add_obstacle(&mut self, row: usize, col: usize) {
-        if let Some(cell) = self.map.get_mut(row, col) {
-            *cell = '#';
+    /// Puts an obstacle on the map, returning whether the position is on it.
+    fn add_obstacle(&mut self, row: usize, col: usize) -> bool {
+        match self.map.get_mut((row, col)) {
+            Some(cell) => {
+                *cell = '#';
+                true
+            }
+            None => false,
         }
     }
 
@@ -104,43 +136,55 @@ impl SuitLabMap {
             .len()
     }
 
-    #[allow(dead_code)]
     fn get_annotated_map(&self) -> Grid<char> {
         let mut map = self.map.clone();
 
         // annotate the places the guard has been with an X
-        for (index, value) in map.indexed_iter_mut() {
-            if self
-                .guard_history
-                .iter()
-                .any(|&(col, row, _)| col == index.0 && row == index.1)
-            {
-                *value = 'X';
-            }
+        for &(row, col, _) in self.guard_history.iter() {
+            map[(row, col)] = 'X';
         }
         map
     }
 
-    fn step(&mut self) -> GuardStatus {
-        let mut next_position = self.guard_position;
-
-        let (dr, dc) = self.guard_orientation.step_direction();
+    /// The annotated map with the guard on top, as text.
+    fn render_map(&self) -> String {
+        let mut map = self.get_annotated_map();
+        // the guard only turns right from north, so it always has an arrow
+        map[self.guard_position] = self.guard_orientation.arrow().unwrap();
+        map.to_string().trim_end().to_string()
+    }
 
-        let new_row = next_position.0 as isize + dr;
-        let new_col = next_position.1 as isize + dc;
+    // obstacles in gray, the visited positions in orange, the start and the guard as dots
+    fn picture(&self) -> Picture {
+        let map = self.get_annotated_map();
+        let mut picture = Picture::new(map.rows(), map.cols(), |row, col| match map[(row, col)] {
+            '#' => Rgb::GRAY,
+            'X' => Rgb(255, 190, 110),
+            _ => Rgb::WHITE,
+        });
+        picture.marker(self.guard_starting_position, Rgb(40, 150, 70));
+        picture.marker(self.guard_position, Rgb::RED);
+        picture
+    }
 
-        if new_row < 0
-            || new_col < 0
-            || new_row as usize >= self.map.rows()
-            || new_col as usize >= self.map.cols()
-        {
-            return GuardStatus::Finished;
+    /// The guard back at the start, before walking. Obstacles added since
+    /// parsing stay on the map.
+    fn reset(&self) -> SuitLabMap {
+        let position = self.guard_starting_position;
+        SuitLabMap {
+            guard_position: position,
+            guard_orientation: Direction::N,
+            guard_history: HashSet::from([(position.0, position.1, Direction::N)]),
+            ..self.clone()
         }
+    }
 
-        next_position.0 = new_row as usize;
-        next_position.1 = new_col as usize;
+    fn step(&mut self) -> GuardStatus {
+        let Some(next_position) = self.map.step(self.guard_position, self.guard_orientation) else {
+            return GuardStatus::Finished;
+        };
 
-        match self.map.get(next_position.0, next_position.1) {
+        match self.map.get(next_position) {
             Some('.') => {
                 self.guard_position = next_position;
                 if self.guard_history.insert((
@@ -154,7 +198,7 @@ impl SuitLabMap {
                 }
             }
             Some('#') => {
-                self.guard_orientation = self.guard_orientation.rotate_right();
+                self.guard_orientation = self.guard_orientation.turn_right();
                 GuardStatus::Normal
             }
             _ => {
@@ -164,88 +208,144 @@ impl SuitLabMap {
     }
 }
 
+impl Repl for SuitLabMap {
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, ReplError> {
+        match name {
+            "step" => {
+                let n_steps: usize = match args {
+                    [] => 1,
+                    [n] => repl::parse_arg(n)?,
+                    _ => return Err(ReplError::Usage("usage: step [count]")),
+                };
+                let mut status = GuardStatus::Normal;
+                for _ in 0..n_steps {
+                    status = self.step();
+                    if status != GuardStatus::Normal {
+                        break;
+                    }
+                }
+                Ok(format!(
+                    "guard at {:?} facing {:?}, status {:?}",
+                    self.guard_position, self.guard_orientation, status
+                ))
+            }
+            "show" => Ok(self.render_map()),
+            "at" => {
+                let (row, col) = repl::parse_position(args)?;
+                let cell = self
+                    .map
+                    .get((row, col))
+                    .ok_or(ReplError::Usage("position is outside of the map"))?;
+                let orientations: Vec<Direction> = self
+                    .guard_history
+                    .iter()
+                    .filter(|&&(r, c, _)| (r, c) == (row, col))
+                    .map(|&(_, _, orientation)| orientation)
+                    .collect();
+                Ok(format!("{cell:?}, visited facing {orientations:?}"))
+            }
+            "obstacle" => {
+                let (row, col) = repl::parse_position(args)?;
+                match self.add_obstacle(row, col) {
+                    true => Ok(String::new()),
+                    false => Err(ReplError::InvalidArgument(format!(
+                        "{row} {col}, the position is off the map"
+                    ))),
+                }
+            }
+            "reset" => {
+                *self = self.reset();
+                Ok(String::new())
+            }
+            "run" => match args {
+                ["part1"] => {
+                    while self.step() == GuardStatus::Normal {}
+                    Ok(format!(
+                        "the guard has visited {} unique positions",
+                        self.get_n_guard_positions()
+                    ))
+                }
+                ["part2"] => {
+                    let start = self.reset();
+                    let mut walked = start.clone();
+                    while walked.step() == GuardStatus::Normal {}
+                    let locations = find_loop_obstacles(&start, &walked);
+                    Ok(format!(
+                        "Found {} unique obstacle placement locations",
+                        locations.len()
+                    ))
+                }
+                _ => Err(ReplError::Usage("usage: run part1|part2")),
+            },
+            _ => Err(ReplError::UnknownCommand(name.to_string())),
+        }
+    }
+
+    fn help(&self) -> &[&str] {
+        &[
+            "step [count]    move the guard",
+            "show            print the map with the visited positions",
+            "at <row> <col>  show a cell and the guard's visits",
+            "obstacle <row> <col>",
+            "reset           put the guard back at the start, keeping the obstacles",
+            "run part1|part2",
+        ]
+    }
+
+    fn prompt(&self) -> String {
+        String::from("day06> ")
+    }
+}
+
+impl Simulation for SuitLabMap {
+    fn step(&mut self) -> bool {
+        SuitLabMap::step(self) == GuardStatus::Normal
+    }
+
+    fn render(&self) -> String {
+        self.render_map()
+    }
+}
+
 impl FromStr for SuitLabMap {
-    type Err = SuitLabMapError;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.trim().is_empty() {
-            return Err(SuitLabMapError::EmptyInput);
+        // the map can only hold '.', '#' and the guard, anything else would stop the guard
+        let validator = lint::validator(6).expect("day 6 has a validator");
+        if let Some(diagnostic) = validator.check(s).first() {
+            return Err(diagnostic.to_parse_error(s));
         }
 
-        let map_length: usize = s.lines().next().unwrap().chars().count();
-
-        let mut map = Grid::from_vec(s.lines().flat_map(|l| l.chars()).collect(), map_length);
+        let mut map: Grid<char> = Grid::parse(s, Some)?;
 
         // search for the guard, denoted by ^
-        let mut guard_position = None;
-        for ((row, col), value) in map.indexed_iter_mut() {
-            if *value == '^' {
-                guard_position = Some((row, col));
-                *value = '.';
-                break;
-            }
+        let guard_position = map.positions(|&c| c == '^').next();
+        if let Some(position) = guard_position {
+            map[position] = '.';
         }
 
-        let mut guard_unique_position_orientation_history: HashSet<(usize, usize, Orientation)> =
+        let mut guard_unique_position_orientation_history: HashSet<(usize, usize, Direction)> =
             HashSet::new();
 
         if let Some(position) = guard_position {
             guard_unique_position_orientation_history.insert((
                 position.0,
                 position.1,
-                Orientation::Up,
+                Direction::N,
             ));
             Ok(SuitLabMap {
                 guard_starting_position: position,
                 guard_position: position,
-                guard_orientation: Orientation::Up,
+                guard_orientation: Direction::N,
                 map,
                 guard_history: {
                     let mut set = HashSet::new();
-                    set.insert((position.0, position.1, Orientation::Up));
+                    set.insert((position.0, position.1, Direction::N));
                     set
                 },
             })
         } else {
-            Err(SuitLabMapError::GuardNotFound)
-        }
-    }
-}
-
-#[derive(Debug)]
-enum SuitLabMapError {
-    EmptyInput,
-    GuardNotFound,
-}
-
-impl fmt::Display for SuitLabMapError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            SuitLabMapError::EmptyInput => write!(f, "Input is empty"),
-            SuitLabMapError::GuardNotFound => write!(f, "Guard not found in the map"),
-        }
-    }
-}
-
-impl Error for SuitLabMapError {}
-
-impl Orientation {
-    fn step_direction(&self) -> (isize, isize) {
-        use Orientation::*;
-        match self {
-            Up => (-1, 0),
-            Right => (0, 1),
-            Down => (1, 0),
-            Left => (0, -1),
-        }
-    }
-
-    fn rotate_right(&mut self) -> Orientation {
-        use Orientation::*;
-        match self {
-            Up => Right,
-            Right => Down,
-            Down => Left,
-            Left => Up,
+            Err(ParseError::new(s, 1, 1, "guard '^' not found in the map"))
         }
     }
 }
@@ -1,10 +1,43 @@
 use std::collections::{HashMap, HashSet};
 
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::counters;
+use advent_of_code_2024::explain::Explain;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day07.txt");
+    let config = config::load();
+    let input = config.read_input(7);
+    lint::check_or_exit(7, &input);
 
+    let bridge_equations = parse_equations(&input);
+
+    let explain = Explain::new(&config, 7);
+    if explain.enabled() {
+        for (part, concat_included) in [(1, false), (2, true)] {
+            for bridge_equation in bridge_equations.iter() {
+                if let Some(expression) = bridge_equation.expression(concat_included) {
+                    let details = serde_json::json!({
+                        "test_value": bridge_equation.test_value,
+                        "expression": expression,
+                    });
+                    explain.step(part, "satisfiable equation", details);
+                }
+            }
+        }
+    }
+
+    let answer_a = get_total_calibration_result(&bridge_equations, false);
+    println!("The total sum of the correct equations is {answer_a}");
+    runner::answer(&config, 7, 1, answer_a);
+
+    let answer_b = get_total_calibration_result(&bridge_equations, true);
+    println!("The total sum of the correct equations including the concat operator is {answer_b}");
+    runner::answer(&config, 7, 2, answer_b);
+}
+
+fn parse_equations(input: &str) -> Vec<BridgeEquation> {
     let mut bridge_equations: Vec<BridgeEquation> = vec![];
 
     // parse the input into a vector of bridge equations
@@ -21,33 +54,39 @@ fn main() {
             });
         }
     }
-
-    let answer_a = get_total_calibration_result(&bridge_equations, false);
-    let answer_b = get_total_calibration_result(&bridge_equations, true);
-
-    println!("The total sum of the correct equations is {answer_a}");
-    println!("The total sum of the correct equations including the concat operator is {answer_b}");
+    bridge_equations
 }
 
 fn get_total_calibration_result(bridge_equations: &[BridgeEquation], concat_included: bool) -> u64 {
     let mut memo = HashMap::new();
-    bridge_equations
+    let mut memo_hits = 0;
+    let total = bridge_equations
         .iter()
         .filter(|bridge_equation| {
-            let possibilities =
-                get_equation_possibilities(&bridge_equation.equation, concat_included, &mut memo);
+            let possibilities = get_equation_possibilities(
+                &bridge_equation.equation,
+                concat_included,
+                &mut memo,
+                &mut memo_hits,
+            );
             possibilities.contains(&bridge_equation.test_value)
         })
         .map(|bridge_equation| bridge_equation.test_value)
-        .sum()
+        .sum();
+    // every miss is added to the memo
+    counters::add("memo hits", memo_hits);
+    counters::add("memo misses", memo.len() as u64);
+    total
 }
 
 fn get_equation_possibilities(
     equation: &[u64],
     concat_included: bool,
     memo: &mut HashMap<Vec<u64>, HashSet<u64>>,
+    memo_hits: &mut u64,
 ) -> HashSet<u64> {
     if let Some(cached) = memo.get(equation) {
+        *memo_hits += 1;
         return cached.clone();
     }
 
@@ -59,7 +98,7 @@ fn get_equation_possibilities(
         let last_value = equation.last().copied().unwrap();
         let remaining_equation = &equation[..equation.len() - 1];
         let previous_possibilities =
-            get_equation_possibilities(remaining_equation, concat_included, memo);
+            get_equation_possibilities(remaining_equation, concat_included, memo, memo_hits);
 
         for possibility in previous_possibilities {
             possibilities.insert(possibility * last_value);
@@ -83,3 +122,121 @@ struct BridgeEquation {
     test_value: u64,
     equation: Vec<u64>,
 }
+
+impl BridgeEquation {
+    /// The equation written out with operators that produce the test value,
+    /// like `81 * 40 + 27`.
+    fn expression(&self, concat_included: bool) -> Option<String> {
+        let operators = find_operators(self.test_value, &self.equation, concat_included)?;
+        let mut expression = self.equation[0].to_string();
+        for (operator, value) in operators.iter().zip(self.equation[1..].iter()) {
+            expression.push_str(&format!(" {operator} {value}"));
+        }
+        Some(expression)
+    }
+}
+
+// works backwards from the test value: the last operator must undo into a
+// value the rest of the equation can produce
+fn find_operators(
+    target: u64,
+    equation: &[u64],
+    concat_included: bool,
+) -> Option<Vec<&'static str>> {
+    let (&last_value, rest) = equation.split_last()?;
+    if rest.is_empty() {
+        return (target == last_value).then(Vec::new);
+    }
+
+    let mut candidates = vec![];
+    if target >= last_value {
+        candidates.push(("+", target - last_value));
+    }
+    if last_value != 0 && target.is_multiple_of(last_value) {
+        candidates.push(("*", target / last_value));
+    }
+    if concat_included {
+        let shift = 10u64.pow(last_value.to_string().len() as u32);
+        if target % shift == last_value {
+            candidates.push(("||", target / shift));
+        }
+    }
+
+    candidates.into_iter().find_map(|(operator, remaining)| {
+        let mut operators = find_operators(remaining, rest, concat_included)?;
+        operators.push(operator);
+        Some(operators)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::generate;
+
+    // tries every combination of operators, evaluated left to right
+    fn reference_is_possible(equation: &BridgeEquation, concat_included: bool) -> bool {
+        let n_operators: u32 = if concat_included { 3 } else { 2 };
+        let n_gaps = equation.equation.len() as u32 - 1;
+        (0..n_operators.pow(n_gaps)).any(|mut combination| {
+            let mut result = equation.equation[0];
+            for &value in equation.equation[1..].iter() {
+                result = match combination % n_operators {
+                    0 => result + value,
+                    1 => result * value,
+                    _ => format!("{result}{value}").parse().unwrap(),
+                };
+                combination /= n_operators;
+            }
+            result == equation.test_value
+        })
+    }
+
+    fn reference_total(bridge_equations: &[BridgeEquation], concat_included: bool) -> u64 {
+        bridge_equations
+            .iter()
+            .filter(|e| reference_is_possible(e, concat_included))
+            .map(|e| e.test_value)
+            .sum()
+    }
+
+    #[test]
+    fn test_example() {
+        let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n\
+                     161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20\n";
+        let bridge_equations = parse_equations(input);
+        assert_eq!(get_total_calibration_result(&bridge_equations, false), 3749);
+        assert_eq!(get_total_calibration_result(&bridge_equations, true), 11387);
+        assert_eq!(
+            bridge_equations[1].expression(false).as_deref(),
+            Some("81 * 40 + 27")
+        );
+        assert_eq!(
+            bridge_equations[4].expression(true).as_deref(),
+            Some("6 * 8 || 6 * 15")
+        );
+        assert_eq!(bridge_equations[2].expression(true), None);
+    }
+
+    #[test]
+    fn test_against_reference() {
+        for seed in 0..200 {
+            let input = generate::generate(7, seed, 20).unwrap();
+            let bridge_equations = parse_equations(&input);
+            for concat_included in [false, true] {
+                assert_eq!(
+                    get_total_calibration_result(&bridge_equations, concat_included),
+                    reference_total(&bridge_equations, concat_included),
+                    "{input}"
+                );
+                for equation in bridge_equations.iter() {
+                    assert_eq!(
+                        equation.expression(concat_included).is_some(),
+                        reference_is_possible(equation, concat_included),
+                        "{equation:?}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,39 +1,64 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::image::{self, Picture, Rgb};
+use advent_of_code_2024::lint;
+use advent_of_code_2024::point::Point;
+use advent_of_code_2024::runner;
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
+type Antinodes = HashSet<(usize, usize)>;
+
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day08.txt");
+    let config = config::load();
+    let input = config.read_input(8);
+    lint::check_or_exit(8, &input);
+
+    let (antinodes, antennaline_antinodes) = find_antinodes(&input);
+
+    println!("The number of distinct antinodes is {}", antinodes.len());
+    runner::answer(&config, 8, 1, antinodes.len());
 
+    println!(
+        "the number of unique points on on a line between two antennas is {}",
+        antennaline_antinodes.len()
+    );
+    runner::answer(&config, 8, 2, antennaline_antinodes.len());
+
+    print!("{}", render_antinodes(&input, &antennaline_antinodes));
+
+    if let Some(path) = &config.export {
+        let picture = antinode_picture(&input, &antennaline_antinodes);
+        image::export_or_exit(&config, path, &picture);
+    }
+}
+
+/// The antinodes of part a, and those on any line through two antennas of
+/// part b.
+fn find_antinodes(input: &str) -> (Antinodes, Antinodes) {
     // we need a grid, bounds checking, and iterating over pair
     // I think maybe a hashmap, with characters as the key (antenans),
     // and a vector of locations of those antennas
     // Then we can iterator over each pair, and insert the antinodes into an
     // antinodes hashset made up of tuples of (row, col)
 
-    let grid_height = input.lines().count();
-    let grid_length = input.lines().next().unwrap().chars().count();
+    let grid: Grid<char> = Grid::parse(input, Some).unwrap();
 
     let mut antenna_locations: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
 
-    for (i, line) in input.lines().enumerate() {
-        for (j, char) in line.chars().enumerate() {
-            if char == '.' {
-                continue;
-            }
-            antenna_locations
-                .entry(char)
-                .and_modify(|locations| locations.push((i, j)))
-                .or_insert(vec![(i, j)]);
+    for (position, &char) in grid.iter() {
+        if char == '.' {
+            continue;
         }
+        antenna_locations.entry(char).or_default().push(position);
     }
 
-    let mut antinodes: HashSet<(usize, usize)> = HashSet::new();
+    let mut antinodes: Antinodes = HashSet::new();
     let mut antenna_lines: Vec<AntennaLine> = vec![];
 
     for (_, locations) in antenna_locations.into_iter() {
         locations.iter().combinations(2).for_each(|antenna_pair| {
-            get_antinodes(*antenna_pair[0], *antenna_pair[1], grid_height, grid_length)
+            get_antinodes(&grid, *antenna_pair[0], *antenna_pair[1])
                 .into_iter()
                 .for_each(|antinode| {
                     antinodes.insert(antinode);
@@ -43,35 +68,50 @@ fn main() {
         });
     }
 
-    println!("The number of distinct antinodes is {}", antinodes.len());
-
-    let mut antennaline_antinodes: HashSet<(usize, usize)> = HashSet::new();
+    let mut antennaline_antinodes: Antinodes = HashSet::new();
 
-    for (i, j) in (0..grid_height).cartesian_product(0..grid_length) {
-        for antenna_line in antenna_lines.iter() {
-            if antenna_line.on((i, j)) {
-                antennaline_antinodes.insert((i, j));
-                break;
-            }
+    for (position, _) in grid.iter() {
+        if antenna_lines.iter().any(|line| line.on(position)) {
+            antennaline_antinodes.insert(position);
         }
     }
 
-    println!(
-        "the number of unique points on on a line between two antennas is {}",
-        antennaline_antinodes.len()
-    );
+    (antinodes, antennaline_antinodes)
+}
+
+// the map with the antinodes drawn over it as #
+fn render_antinodes(input: &str, antinodes: &Antinodes) -> String {
+    let grid: Grid<char> = Grid::parse(input, Some).unwrap();
+    Grid::from_fn(grid.rows(), grid.cols(), |row, col| {
+        match antinodes.contains(&(row, col)) {
+            true => '#',
+            false => grid[(row, col)],
+        }
+    })
+    .to_string()
+}
 
-    for (i, line) in input.clone().lines().enumerate() {
-        let line_str: String = line
-            .chars()
-            .enumerate()
-            .map(|(j, x)| match antennaline_antinodes.contains(&(i, j)) {
-                true => '#',
-                false => x,
-            })
-            .collect();
-        println!("{}", line_str);
+// the antennas colored by frequency, with the antinodes as dots
+fn antinode_picture(input: &str, antinodes: &Antinodes) -> Picture {
+    let grid: Grid<char> = Grid::parse(input, Some).unwrap();
+    let frequencies: Vec<char> = grid
+        .iter()
+        .map(|(_, &c)| c)
+        .filter(|&c| c != '.')
+        .sorted()
+        .dedup()
+        .collect();
+
+    let mut picture = Picture::new(grid.rows(), grid.cols(), |row, col| {
+        match frequencies.iter().position(|&f| f == grid[(row, col)]) {
+            Some(index) => image::palette(index),
+            None => Rgb::WHITE,
+        }
+    });
+    for &antinode in antinodes.iter().sorted() {
+        picture.marker(antinode, Rgb::BLACK);
     }
+    picture
 }
 
 #[derive(Debug, PartialEq, PartialOrd)]
@@ -94,26 +134,40 @@ impl AntennaLine {
     }
 }
 
+// the points beyond each antenna, as far from it as the other antenna
 fn get_antinodes(
-    point_a: (usize, usize),
-    point_b: (usize, usize),
-    height: usize,
-    width: usize,
+    grid: &Grid<char>,
+    antenna_a: (usize, usize),
+    antenna_b: (usize, usize),
 ) -> Vec<(usize, usize)> {
-    let row_diff = point_a.0 as isize - point_b.0 as isize;
-    let col_diff = point_a.1 as isize - point_b.1 as isize;
-
-    let antinode1 = (point_a.0 as isize + row_diff, point_a.1 as isize + col_diff);
-    let antinode2 = (point_b.0 as isize - row_diff, point_b.1 as isize - col_diff);
-
-    let mut antinodes = Vec::new();
+    let point_a = Point::from(antenna_a);
+    let point_b = Point::from(antenna_b);
+    let diff = point_a - point_b;
+
+    [point_a + diff, point_b - diff]
+        .into_iter()
+        .filter_map(|antinode| grid.index_of(antinode))
+        .collect()
+}
 
-    if (0..height as isize).contains(&antinode1.0) && (0..width as isize).contains(&antinode1.1) {
-        antinodes.push((antinode1.0 as usize, antinode1.1 as usize));
-    }
-    if (0..height as isize).contains(&antinode2.0) && (0..width as isize).contains(&antinode2.1) {
-        antinodes.push((antinode2.0 as usize, antinode2.1 as usize));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::snapshot::assert_snapshot;
+
+    const EXAMPLE: &str = "............\n........0...\n.....0......\n.......0....\n\
+                           ....0.......\n......A.....\n............\n............\n\
+                           ........A...\n.........A..\n............\n............\n";
+
+    #[test]
+    fn test_example() {
+        let (antinodes, antennaline_antinodes) = find_antinodes(EXAMPLE);
+        assert_eq!(antinodes.len(), 14);
+        assert_eq!(antennaline_antinodes.len(), 34);
+        assert_snapshot(
+            8,
+            "antinodes",
+            &render_antinodes(EXAMPLE, &antennaline_antinodes),
+        );
     }
-
-    antinodes
 }
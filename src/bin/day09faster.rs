@@ -1,75 +1,105 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::animation;
+use advent_of_code_2024::asciicast;
+use advent_of_code_2024::config;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
+use advent_of_code_2024::simulation::Simulation;
+
+// blocks per line when the disk is drawn
+const RENDER_WIDTH: usize = 64;
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day09.txt");
+    let config = config::load();
+    let input = config.read_input(9);
+    lint::check_or_exit(9, &input);
 
-    // parse the input into a vector of options
-    let diskmap: Vec<u32> = input.chars().filter_map(|c| c.to_digit(10)).collect();
+    let diskmap = parse_diskmap(&input);
 
-    let mut disk: Vec<Option<u32>> = vec![];
+    if let Some(path) = &config.record {
+        let mut compactor = DiskCompactor::new(&diskmap);
+        asciicast::record_or_exit(&config, path, &mut compactor);
+        println!("The result for part b was {}", compactor.checksum());
+        return;
+    }
+    if config.animate {
+        let mut compactor = DiskCompactor::new(&diskmap);
+        animation::run(&config, &mut compactor);
+        println!("The result for part b was {}", compactor.checksum());
+        return;
+    }
 
-    let mut id: u32 = 0;
+    let answer_a = compact_blocks(&diskmap);
+    println!("For answer a i have {answer_a}");
+    runner::answer(&config, 9, 1, answer_a);
 
-    for (i, block) in diskmap.iter().enumerate() {
-        if i % 2 == 0 {
-            for _ in 0..*block {
-                disk.push(Some(id));
-            }
-            id += 1;
-        } else {
-            for _ in 0..*block {
-                disk.push(None);
-            }
-        }
-    }
+    let answer_b = compact_files(&diskmap);
+    println!("The result for part b was {answer_b}");
+    runner::answer(&config, 9, 2, answer_b);
+}
+
+fn parse_diskmap(input: &str) -> Vec<u32> {
+    input.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+// part a moves single blocks from the end of the disk into the leftmost free space
+fn compact_blocks(diskmap: &[u32]) -> u64 {
+    let disk = disk(diskmap);
 
     let mut right_idx: usize = disk.len() - 1;
     let mut left_idx: usize = 0;
 
-    let mut answer_a: u64 = 0;
+    let mut checksum: u64 = 0;
 
     while left_idx <= right_idx {
         match disk[left_idx] {
-            Some(id) => answer_a += id as u64 * left_idx as u64,
+            Some(id) => checksum += id as u64 * left_idx as u64,
             None => {
-                while disk[right_idx].is_none() {
+                while right_idx > left_idx && disk[right_idx].is_none() {
                     right_idx -= 1;
                 }
+                // only free space is left, the rest was already moved here
+                if right_idx <= left_idx {
+                    break;
+                }
                 if let Some(id) = disk[right_idx] {
-                    answer_a += id as u64 * left_idx as u64;
+                    checksum += id as u64 * left_idx as u64;
                     right_idx -= 1;
                 }
             }
         }
         left_idx += 1;
     }
+    checksum
+}
 
-    println!("For answer a i have {answer_a}");
-
-    // for part b, stuff on the right can be moved.
-    // We can use the diskmap to find large enough free spaces.
-    // each time we get an element on the right, we can slice into the array
-    // only considering the stuff that comes before it.
-    // So
-    // 1. start at the rightmost element that is data (idx % 2 == 0)
-    // 2. get the id, which is the index / 2
-    // 3. slice throught the array[..idx], and look for a number that is larger or equal and is not data
-    // 4. if found, update the checksum, and decreas that number in the diskmap, if not found, update checksum
-    // 5. go one element to the left and repeat untill done.
-
+// for part b, stuff on the right can be moved.
+// We can use the diskmap to find large enough free spaces.
+// each time we get an element on the right, we can slice into the array
+// only considering the stuff that comes before it.
+// So
+// 1. start at the rightmost element that is data (idx % 2 == 0)
+// 2. get the id, which is the index / 2
+// 3. slice throught the array[..idx], and look for a number that is larger or equal and is not data
+// 4. if found, update the checksum, and decreas that number in the diskmap, if not found, update checksum
+// 5. go one element to the left and repeat untill done.
+fn compact_files(diskmap: &[u32]) -> u64 {
     let mut block_nr = diskmap.len();
-    let mut default_block_start_idx = diskmap[..block_nr - 1].iter().sum::<u32>() as usize;
-    let mut answer_b: u64 = 0;
+    let mut block_end_idx = diskmap.iter().sum::<u32>() as usize;
+    let mut checksum: u64 = 0;
     // we use this diskmap to record the shrinking free spaces
-    let mut free_space_diskmap: Vec<u32> = diskmap.clone();
+    let mut free_space_diskmap: Vec<u32> = diskmap.to_vec();
 
     while block_nr > 0 {
-        if block_nr % 2 != 0 {
+        block_nr -= 1;
+        let block_size: u32 = diskmap[block_nr];
+        block_end_idx -= block_size as usize;
+
+        // free space is skipped, its position is already accounted for
+        if !block_nr.is_multiple_of(2) {
             continue;
         }
 
-        let block_size: u32 = diskmap[block_nr];
-        let mut block_start_idx = default_block_start_idx;
+        let mut block_start_idx = block_end_idx;
         let block_id: u64 = block_nr as u64 / 2;
 
         if let Some(fs_idx) = free_space_diskmap[..block_nr]
@@ -86,12 +116,153 @@ fn main() {
 
         // add to the result
         for i in 0..block_size {
-            answer_b += (block_start_idx as u64 + i as u64) * block_id;
+            checksum += (block_start_idx as u64 + i as u64) * block_id;
         }
+    }
+    checksum
+}
 
-        block_nr -= 1;
-        default_block_start_idx -= block_size as usize;
+/// The whole disk, with the file id of every block.
+fn disk(diskmap: &[u32]) -> Vec<Option<u32>> {
+    diskmap
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &size)| {
+            let id = (i % 2 == 0).then_some(i as u32 / 2);
+            std::iter::repeat_n(id, size as usize)
+        })
+        .collect()
+}
+
+fn checksum(disk: &[Option<u32>]) -> u64 {
+    disk.iter()
+        .enumerate()
+        .map(|(i, id)| i as u64 * id.unwrap_or(0) as u64)
+        .sum()
+}
+
+/// Part b on the full disk, one file at a time. Far slower than
+/// [`compact_files`], but it can be watched.
+struct DiskCompactor {
+    disk: Vec<Option<u32>>,
+    // the file to move next, files move in order of decreasing id
+    next_id: Option<u32>,
+}
+
+impl DiskCompactor {
+    fn new(diskmap: &[u32]) -> Self {
+        let next_id = (diskmap.len() as u32).div_ceil(2).checked_sub(1);
+        DiskCompactor {
+            disk: disk(diskmap),
+            next_id,
+        }
     }
 
-    println!("The result for part b was {answer_b}");
+    fn checksum(&self) -> u64 {
+        checksum(&self.disk)
+    }
+}
+
+impl Simulation for DiskCompactor {
+    fn step(&mut self) -> bool {
+        let Some(id) = self.next_id else {
+            return false;
+        };
+        self.next_id = id.checked_sub(1);
+
+        let Some(start) = self.disk.iter().position(|&b| b == Some(id)) else {
+            return true;
+        };
+        let size = self.disk[start..]
+            .iter()
+            .take_while(|&&b| b == Some(id))
+            .count();
+        let free_start = (0..start).find(|&i| self.disk[i..i + size].iter().all(|b| b.is_none()));
+        if let Some(free_start) = free_start {
+            for i in 0..size {
+                self.disk.swap(free_start + i, start + i);
+            }
+        }
+        true
+    }
+
+    // files are drawn with the last digit of their id, free space as a dot
+    fn render(&self) -> String {
+        self.disk
+            .chunks(RENDER_WIDTH)
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .map(|block| match block {
+                        Some(id) => char::from_digit(id % 10, 10).unwrap(),
+                        None => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::generate;
+
+    // moves the last block into the first free space until the free space is at the end
+    fn reference_blocks(diskmap: &[u32]) -> u64 {
+        let mut disk = disk(diskmap);
+        while let Some(free) = disk.iter().position(|b| b.is_none()) {
+            let last = disk.iter().rposition(|b| b.is_some()).unwrap();
+            if last < free {
+                break;
+            }
+            disk.swap(free, last);
+        }
+        checksum(&disk)
+    }
+
+    // moves every file, highest id first, to the leftmost free span that fits it
+    fn reference_files(diskmap: &[u32]) -> u64 {
+        let mut compactor = DiskCompactor::new(diskmap);
+        while compactor.step() {}
+        compactor.checksum()
+    }
+
+    #[test]
+    fn test_example() {
+        let diskmap = parse_diskmap("2333133121414131402");
+        assert_eq!(compact_blocks(&diskmap), 1928);
+        assert_eq!(compact_files(&diskmap), 2858);
+
+        let mut compactor = DiskCompactor::new(&diskmap);
+        assert_eq!(
+            compactor.render(),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        while compactor.step() {}
+        assert_eq!(
+            compactor.render(),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+        assert_eq!(compactor.checksum(), 2858);
+    }
+
+    #[test]
+    fn test_against_reference() {
+        for seed in 0..300 {
+            let input = generate::generate(9, seed, 1 + seed as usize % 20).unwrap();
+            let diskmap = parse_diskmap(&input);
+            assert_eq!(
+                compact_blocks(&diskmap),
+                reference_blocks(&diskmap),
+                "{input}"
+            );
+            assert_eq!(
+                compact_files(&diskmap),
+                reference_files(&diskmap),
+                "{input}"
+            );
+        }
+    }
 }
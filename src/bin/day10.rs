@@ -1,30 +1,19 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::image::{self, Picture, Rgb};
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
+use advent_of_code_2024::search::{self, ShortestPaths};
 use std::collections::HashSet;
 
 fn main() {
-    let input = file_reader::get_input(&format!(
-        "{}/src/inputs/test_day10.txt",
-        env!("CARGO_MANIFEST_DIR")
-    ));
+    let config = config::load();
+    let input = config.read_input(10);
+    lint::check_or_exit(10, &input);
 
-    let map_grid: Vec<Vec<u8>> = input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .filter_map(|c| c.to_digit(10).map(|d| d as u8))
-                .collect()
-        })
-        .collect();
-
-    let mut trailheads: Vec<(usize, usize)> = vec![];
+    let map_grid: Grid<u8> = Grid::parse(&input, |c| c.to_digit(10).map(|d| d as u8)).unwrap();
 
-    map_grid.iter().enumerate().for_each(|(i, row)| {
-        row.iter().enumerate().for_each(|(j, &element)| {
-            if element == 0 {
-                trailheads.push((i, j));
-            }
-        });
-    });
+    let trailheads: Vec<(usize, usize)> = map_grid.positions(|&height| height == 0).collect();
 
     let mut trailhead_ends = vec![];
 
@@ -42,104 +31,68 @@ fn main() {
             .collect::<Vec<usize>>(),
         answer_a
     );
+    runner::answer(&config, 10, 1, answer_a);
 
-    let mut trailhead_paths = vec![];
+    let mut trailhead_trails = vec![];
 
     for &trailhead in trailheads.iter() {
-        let path: Vec<(usize, usize)> = vec![trailhead];
-        let mut paths = HashSet::new();
-        paths.insert(path);
-        trailhead_paths.push(find_unique_trails(&map_grid, paths, trailhead))
+        trailhead_trails.push(search::bfs_all(trailhead, |&coords| {
+            uphill(&map_grid, coords)
+        }))
     }
 
-    let answer_b = trailhead_paths.iter().fold(0, |acc, x| acc + x.len());
+    let trailhead_ratings: Vec<u64> = trailhead_trails
+        .iter()
+        .map(|trails| count_unique_trails(&map_grid, trails))
+        .collect();
+    let answer_b: u64 = trailhead_ratings.iter().sum();
 
     println!(
         "the trailheads have scores of {:?}, making for a total score of {}",
-        trailhead_paths
-            .iter()
-            .map(|x| x.len())
-            .collect::<Vec<usize>>(),
-        answer_b
+        trailhead_ratings, answer_b
     );
+    runner::answer(&config, 10, 2, answer_b);
 
-    // we can also get answer a from the paths
-    // let answer_a: usize = trailhead_paths
-    //     .iter()
-    //     .map(|h| h.iter().map(|l| l.last()).unique().count()) // count how many unique endpoints each set of trails has
-    //     .sum();
-}
-
-fn find_unique_trails(
-    map: &Vec<Vec<u8>>,
-    paths: HashSet<Vec<(usize, usize)>>,
-    coords: (usize, usize),
-) -> HashSet<Vec<(usize, usize)>> {
-    let map_size = (map.len(), map[0].len());
-    let current_height = map[coords.0][coords.1];
-
-    let paths_till_here: HashSet<Vec<(usize, usize)>> = paths
-        .iter()
-        .map(|path| {
-            let mut new_path = path.clone();
-            new_path.push(coords);
-            new_path
-        })
-        .collect();
-    if current_height == 9 {
-        return paths_till_here;
-    }
-
-    let mut updated_paths: HashSet<Vec<(usize, usize)>> = HashSet::new();
-
-    for adjacent_spot in get_adjacent_coords_in_bounds(coords, map_size) {
-        if map[adjacent_spot.0][adjacent_spot.1] == current_height + 1 {
-            for paths in find_unique_trails(map, paths_till_here.clone(), adjacent_spot) {
-                updated_paths.insert(paths);
+    if let Some(path) = &config.export {
+        // the heights, with every hiking trail drawn from its trailhead
+        let mut picture = Picture::new(map_grid.rows(), map_grid.cols(), |row, col| {
+            image::gradient(map_grid[(row, col)] as f64 / 9.0)
+        });
+        for trails in trailhead_trails.iter() {
+            for (&coords, previous_spots) in trails.predecessors.iter() {
+                for &previous_spot in previous_spots {
+                    picture.path(vec![previous_spot, coords], Rgb::RED);
+                }
             }
         }
-    }
-    updated_paths
-}
-
-fn find_unique_trail_ends(map: &Vec<Vec<u8>>, coords: (usize, usize)) -> HashSet<(usize, usize)> {
-    let map_size = (map.len(), map[0].len());
-    let current_height = map[coords.0][coords.1];
-    let mut trail_ends = HashSet::new();
-    // check if we reached the top of the trail
-    if map[coords.0][coords.1] == 9 {
-        trail_ends.insert(coords);
-        return trail_ends;
-    }
-
-    // check adjacent spots and add children
-    for adjacent_spot in get_adjacent_coords_in_bounds(coords, map_size) {
-        if map[adjacent_spot.0][adjacent_spot.1] == current_height + 1 {
-            for trail_end in find_unique_trail_ends(map, adjacent_spot) {
-                trail_ends.insert(trail_end);
-            }
+        for &trailhead in trailheads.iter() {
+            picture.marker(trailhead, Rgb::BLACK);
         }
+        image::export_or_exit(&config, path, &picture);
     }
-
-    trail_ends
 }
 
-fn get_adjacent_coords_in_bounds(
-    coords: (usize, usize),
-    map_size: (usize, usize),
-) -> Vec<(usize, usize)> {
-    let mut adjacent_coords = Vec::new();
-    // Define the possible directions (up, down, right, left)
-    let directions = [(-1, 0), (1, 0), (0, 1), (0, -1)];
-
-    for (dx, dy) in directions.iter() {
-        let new_x = coords.0 as isize + dx;
-        let new_y = coords.1 as isize + dy;
+// a trail climbs one height per step, so all trails to a top are equally
+// long and each of them is one of the shortest paths there
+fn count_unique_trails(map: &Grid<u8>, trails: &ShortestPaths<(usize, usize)>) -> u64 {
+    trails
+        .cost
+        .keys()
+        .filter(|&&coords| map[coords] == 9)
+        .map(|top| trails.count_paths(top).expect("too many trails to count"))
+        .sum()
+}
 
-        if new_x >= 0 && new_x < map_size.0 as isize && new_y >= 0 && new_y < map_size.1 as isize {
-            adjacent_coords.push((new_x as usize, new_y as usize));
-        }
-    }
+// the tops reachable from a trailhead
+fn find_unique_trail_ends(map: &Grid<u8>, trailhead: (usize, usize)) -> HashSet<(usize, usize)> {
+    search::reachable([trailhead], |&coords| uphill(map, coords))
+        .into_iter()
+        .filter(|&coords| map[coords] == 9)
+        .collect()
+}
 
-    adjacent_coords
+// the adjacent spots one higher, where a trail can go next
+fn uphill(map: &Grid<u8>, coords: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+    map.neighbors4(coords)
+        .filter(move |&adjacent_spot| map[adjacent_spot] == map[coords] + 1)
 }
@@ -1,8 +1,12 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config::{self, Config};
+use advent_of_code_2024::lint;
+use advent_of_code_2024::runner;
 use std::collections::HashMap;
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day11.txt");
+    let config = config::load();
+    let input = config.read_input(11);
+    lint::check_or_exit(11, &input);
 
     let mut stones: HashMap<u64, u64> = HashMap::new();
 
@@ -13,9 +17,9 @@ fn main() {
             stones.entry(x).and_modify(|v| *v += 1).or_insert(1);
         });
 
-    let n_blinks = 75;
+    let n_blinks = config.day11.n_blinks;
 
-    for _ in 0..n_blinks {
+    for i in 1..=n_blinks {
         let mut new_stones: HashMap<u64, u64> = HashMap::new();
         for (stone, count) in stones.iter() {
             let stone_result = blink(*stone);
@@ -27,11 +31,24 @@ fn main() {
             }
         }
         stones = new_stones;
+
+        if i == 25 || i == n_blinks {
+            report_stones(&config, i, &stones);
+        }
     }
+}
 
-    let answer_a: u64 = stones.values().sum();
+fn report_stones(config: &Config, n_blinks: u32, stones: &HashMap<u64, u64>) {
+    let n_stones: u64 = stones.values().sum();
+    println!("after blinking {n_blinks} times, there are {n_stones} stones");
 
-    println!("after blinking {n_blinks} times, there are {answer_a} stones");
+    // the parts only differ in the number of blinks
+    let part = match n_blinks {
+        25 => 1,
+        75 => 2,
+        _ => return,
+    };
+    runner::answer(config, 11, part, n_stones);
 }
 
 fn blink(stone: u64) -> Vec<u64> {
@@ -40,7 +57,7 @@ fn blink(stone: u64) -> Vec<u64> {
     };
     let stone_str = stone.to_string();
     let n_digits = stone_str.len();
-    if n_digits % 2 == 0 {
+    if n_digits.is_multiple_of(2) {
         let mid = n_digits / 2;
         let (left, right) = stone_str.split_at(mid);
         return vec![left.parse::<u64>().unwrap(), right.parse::<u64>().unwrap()];
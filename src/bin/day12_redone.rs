@@ -1,48 +1,58 @@
-use advent_of_code_2024::file_reader;
-use std::collections::{HashMap, HashSet, VecDeque};
-
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+use advent_of_code_2024::config;
+use advent_of_code_2024::counters;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::image::{self, Picture};
+use advent_of_code_2024::lint;
+use advent_of_code_2024::region::{self, Connectivity, Regions};
+use advent_of_code_2024::runner;
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day12.txt");
-
-    let garden: HashMap<(isize, isize), char> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(i, l)| {
-            l.chars()
-                .enumerate()
-                .map(move |(j, c)| ((i as isize, j as isize), c))
-        })
-        .collect();
-
-    let mut garden_copy = garden.clone();
-
-    let mut total_fence_price: u32 = 0;
-
-    while let Some((&plot, _)) = garden_copy.iter().next() {
-        let mut fence_length = 0;
-        let mut plot_area = 0;
-        let mut queue = VecDeque::from([plot]);
-        let mut seen = HashSet::new();
-        seen.insert(plot);
-
-        while let Some(current_plot) = queue.pop_front() {
-            for dir in DIRECTIONS.iter() {
-                let plot_to_check = (current_plot.0 + dir.0, current_plot.1 + dir.1);
-
-                if garden.get(&plot_to_check) != Some(&garden[&plot]) {
-                    fence_length += 1;
-                } else if seen.insert(plot_to_check) {
-                    queue.push_back(plot_to_check);
-                }
-            }
-            plot_area += 1;
-            garden_copy.remove(&current_plot);
-        }
-
-        total_fence_price += fence_length * plot_area;
-    }
+    let config = config::load();
+    let input = config.read_input(12);
+    lint::check_or_exit(12, &input);
+
+    let garden: Grid<char> = Grid::parse(&input, Some).unwrap();
+    let regions = region::label(&garden, Connectivity::Four, |a, b| a == b);
+    counters::add("regions", regions.regions.len() as u64);
+
+    let total_fence_price = fence_price(&regions);
 
     println!("The total fence price is {total_fence_price}");
+    runner::answer(&config, 12, 1, total_fence_price);
+
+    if let Some(path) = &config.export {
+        image::export_or_exit(&config, path, &region_picture(&regions));
+    }
+}
+
+fn fence_price(regions: &Regions) -> usize {
+    regions
+        .regions
+        .iter()
+        .map(|region| region.area() * region.perimeter)
+        .sum()
+}
+
+// every region in its own color, labeled in reading order so they are the same every run
+fn region_picture(regions: &Regions) -> Picture {
+    let labels = &regions.labels;
+    Picture::new(labels.rows(), labels.cols(), |row, col| {
+        image::palette(labels[(row, col)])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "RRRRIICCFF\nRRRRIICCCF\nVVRRRCCFFF\nVVRCCCJFFF\nVVVVCJJCFE\n\
+                           VVIVCCJJEE\nVVIIICJJEE\nMIIIIIJJEE\nMIIISIJEEE\nMMMISSJEEE\n";
+
+    #[test]
+    fn test_example() {
+        let garden: Grid<char> = Grid::parse(EXAMPLE, Some).unwrap();
+        let regions = region::label(&garden, Connectivity::Four, |a, b| a == b);
+        assert_eq!(regions.regions.len(), 11);
+        assert_eq!(fence_price(&regions), 1930);
+    }
 }
@@ -1,94 +1,200 @@
-use advent_of_code_2024::file_reader;
+use advent_of_code_2024::config;
+use advent_of_code_2024::explain::Explain;
+use advent_of_code_2024::lint;
+use advent_of_code_2024::point::Point;
+use advent_of_code_2024::runner;
 use regex::Regex;
 
 const A_BUTTON_COST: i64 = 3;
 const B_BUTTON_COST: i64 = 1;
-const MAX_BUTTON_PRESSES: u8 = 100;
 
 fn main() {
-    let input = file_reader::get_input("src/inputs/input_day13.txt");
+    let config = config::load();
+    let input = config.read_input(13);
+    lint::check_or_exit(13, &input);
 
-    let machines: Vec<GrabMachine> = input
-        .split("\n\n")
-        .map(|s| {
-            let re = Regex::new(r"\d+").unwrap();
-            let coords: Vec<i64> = re
-                .captures_iter(s)
-                .map(|c| c[0].parse::<i64>().unwrap())
-                .take(6)
-                .collect();
-            GrabMachine {
-                a: (coords[0], coords[1]),
-                b: (coords[2], coords[3]),
-                prize: (coords[4], coords[5]),
-            }
-        })
-        .collect();
+    let machines = parse_machines(&input);
+    let explain = Explain::new(&config, 13);
 
     let machine_tokens: Vec<i64> = machines
         .iter()
-        .map(|m| m.calculate_grab_tokens(true))
+        .map(|m| m.calculate_grab_tokens(Some(config.day13.max_button_presses)))
         .collect();
     let answer_a: i64 = machine_tokens.iter().sum();
+    if explain.enabled() {
+        explain_machines(
+            &explain,
+            1,
+            &machines,
+            Some(config.day13.max_button_presses),
+        );
+    }
 
     println!("The answer to part a is {answer_a:?}");
+    runner::answer(&config, 13, 1, answer_a);
 
+    let offset = config.day13.prize_offset;
     let updated_machines: Vec<GrabMachine> = machines
         .iter()
         .map(|m| GrabMachine {
-            prize: (m.prize.0 + 10000000000000, m.prize.1 + 10000000000000),
+            prize: m.prize + Point::new(offset, offset),
             ..m.clone()
         })
         .collect();
 
     let updated_machine_tokens: Vec<i64> = updated_machines
         .iter()
-        .map(|m| m.calculate_grab_tokens(false))
+        .map(|m| m.calculate_grab_tokens(None))
         .collect();
 
     let answer_b: i64 = updated_machine_tokens.iter().sum();
+    if explain.enabled() {
+        explain_machines(&explain, 2, &updated_machines, None);
+    }
 
     println!("The answer to part b is {answer_b:?}");
+    runner::answer(&config, 13, 2, answer_b);
+}
+
+fn explain_machines(
+    explain: &Explain,
+    part: u8,
+    machines: &[GrabMachine],
+    press_limit: Option<i64>,
+) {
+    for (i, machine) in machines.iter().enumerate() {
+        let details = match machine.solve(press_limit) {
+            Some((presses_a, presses_b)) => serde_json::json!({
+                "machine": i + 1,
+                "presses_a": presses_a,
+                "presses_b": presses_b,
+                "tokens": machine.calculate_grab_tokens(press_limit),
+            }),
+            None => serde_json::json!({"machine": i + 1, "prize": "unreachable"}),
+        };
+        explain.step(part, "machine", details);
+    }
+}
+
+fn parse_machines(input: &str) -> Vec<GrabMachine> {
+    let re = Regex::new(r"\d+").unwrap();
+    input
+        .split("\n\n")
+        .map(|s| {
+            let coords: Vec<i64> = re
+                .captures_iter(s)
+                .map(|c| c[0].parse::<i64>().unwrap())
+                .take(6)
+                .collect();
+            GrabMachine {
+                a: Point::new(coords[0], coords[1]),
+                b: Point::new(coords[2], coords[3]),
+                prize: Point::new(coords[4], coords[5]),
+            }
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone)]
 struct GrabMachine {
-    a: (i64, i64),
-    b: (i64, i64),
-    prize: (i64, i64),
+    a: Point,
+    b: Point,
+    prize: Point,
 }
 
 impl GrabMachine {
-    fn calculate_grab_tokens(&self, press_limit: bool) -> i64 {
-        let a0 = self.a.0;
-        let a1 = self.a.1;
-        let b0 = self.b.0;
-        let b1 = self.b.1;
+    fn calculate_grab_tokens(&self, press_limit: Option<i64>) -> i64 {
+        match self.solve(press_limit) {
+            Some((presses_a, presses_b)) => A_BUTTON_COST * presses_a + B_BUTTON_COST * presses_b,
+            None => 0,
+        }
+    }
+
+    /// The number of presses of the a and b buttons that reach the prize.
+    fn solve(&self, press_limit: Option<i64>) -> Option<(i64, i64)> {
+        let Point { x: a0, y: a1 } = self.a;
+        let Point { x: b0, y: b1 } = self.b;
 
         // Prize coordinates
-        let prize0 = self.prize.0;
-        let prize1 = self.prize.1;
+        let Point {
+            x: prize0,
+            y: prize1,
+        } = self.prize;
 
         // Determinant of the coefficient matrix
         let det = a0 * b1 - a1 * b0;
 
+        // parallel buttons have no unique solution, they don't occur in the puzzle inputs
+        if det == 0 {
+            return None;
+        }
+
         // Calculate the numerator for presses_a and presses_b
         let num_presses_a = -(b0 * prize1 - b1 * prize0);
         let num_presses_b = -(a1 * prize0 - a0 * prize1);
 
         // Check if the results are integers
         if num_presses_a % det != 0 || num_presses_b % det != 0 {
-            return 0; // No valid integer solution
+            return None; // No valid integer solution
         }
 
         // Calculate the number of presses for each button
         let presses_a = num_presses_a / det;
         let presses_b = num_presses_b / det;
 
-        if !press_limit || (presses_a <= 100 && presses_b <= 100) {
-            A_BUTTON_COST * presses_a + B_BUTTON_COST * presses_b
-        } else {
-            0
+        // a button can't be pressed a negative number of times
+        if presses_a < 0 || presses_b < 0 {
+            return None;
+        }
+
+        press_limit
+            .is_none_or(|limit| presses_a <= limit && presses_b <= limit)
+            .then_some((presses_a, presses_b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::generate;
+
+    // the cheapest combination of presses reaching the prize
+    fn reference_tokens(machine: &GrabMachine, press_limit: i64) -> i64 {
+        let mut cheapest = None;
+        for presses_a in 0..=press_limit {
+            for presses_b in 0..=press_limit {
+                let position = machine.a * presses_a + machine.b * presses_b;
+                if position == machine.prize {
+                    let tokens = A_BUTTON_COST * presses_a + B_BUTTON_COST * presses_b;
+                    cheapest = Some(cheapest.map_or(tokens, |c: i64| c.min(tokens)));
+                }
+            }
+        }
+        cheapest.unwrap_or(0)
+    }
+
+    #[test]
+    fn test_example() {
+        let machines = parse_machines(
+            "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\n\
+             Button A: X+26, Y+66\nButton B: X+67, Y+21\nPrize: X=12748, Y=12176\n",
+        );
+        assert_eq!(machines[0].calculate_grab_tokens(Some(100)), 280);
+        assert_eq!(machines[0].solve(Some(100)), Some((80, 40)));
+        assert_eq!(machines[1].calculate_grab_tokens(Some(100)), 0);
+    }
+
+    #[test]
+    fn test_against_reference() {
+        for seed in 0..100 {
+            let input = generate::generate(13, seed, 5).unwrap();
+            for machine in parse_machines(&input) {
+                assert_eq!(
+                    machine.calculate_grab_tokens(Some(100)),
+                    reference_tokens(&machine, 100),
+                    "{machine:?}"
+                );
+            }
         }
     }
 }
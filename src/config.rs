@@ -0,0 +1,485 @@
+//! Settings shared by the day binaries.
+//!
+//! Values are read from `aoc.toml` in the crate root and can be overridden on
+//! the command line, e.g. `--input-dir other/inputs` or `--set day11.n_blinks=25`.
+//! Relative paths are resolved against the directory of the configuration file.
+
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Year of the event, for leaderboard exports that don't name one
+    pub year: u16,
+    pub input_dir: PathBuf,
+    pub answers: PathBuf,
+    pub progress: PathBuf,
+    pub archive_dir: PathBuf,
+    /// Answers to the examples, extracted from the archived puzzles
+    pub examples: PathBuf,
+    /// Maximum run time of a single day, in seconds
+    pub timeout: u64,
+    /// Number of days run at the same time by `aoc all`, 0 for one per core
+    pub jobs: usize,
+    pub recording: RecordingConfig,
+    pub image: ImageConfig,
+    pub bench: BenchConfig,
+    pub day04: Day04Config,
+    pub day11: Day11Config,
+    pub day13: Day13Config,
+    /// Input file given with `--input`, used instead of the default input of the day
+    #[serde(skip)]
+    pub input: Option<PathBuf>,
+    /// Start an interactive session instead of solving, see [`crate::repl`]
+    #[serde(skip)]
+    pub repl: bool,
+    /// Record the simulation of the day to this file, see [`crate::asciicast`]
+    #[serde(skip)]
+    pub record: Option<PathBuf>,
+    /// Animate the simulation of the day in the terminal, see [`crate::animation`]
+    #[serde(skip)]
+    pub animate: bool,
+    /// Export a picture of the grid to this file, see [`crate::image`]
+    #[serde(skip)]
+    pub export: Option<PathBuf>,
+    /// Allow commands to replace existing files
+    #[serde(skip)]
+    pub force: bool,
+    /// Print reports as JSON instead of text
+    #[serde(skip)]
+    pub json: bool,
+    /// Trace how the answers are derived, see [`crate::explain`]
+    #[serde(skip)]
+    pub explain: bool,
+    /// Positional arguments, taken by the commands of the runner
+    #[serde(skip)]
+    pub args: Vec<String>,
+    /// The flags given on the command line, with their values
+    #[serde(skip)]
+    pub flags: Vec<String>,
+    /// The keys set with `--set`
+    #[serde(skip)]
+    overrides: Vec<String>,
+    #[serde(skip)]
+    root: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecordingConfig {
+    pub frame_delay_ms: u64,
+    pub steps_per_frame: usize,
+    /// Frames of a recording before it skips to the final state, 0 for no limit
+    pub max_frames: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageConfig {
+    /// Width and height of a grid cell, in pixels
+    pub cell_size: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchConfig {
+    /// Timings of earlier benchmarks, by commit
+    pub history: PathBuf,
+    /// Runs of every binary, of which the fastest counts
+    pub repeats: usize,
+    /// Increase in run time, in percent, reported as a slowdown
+    pub slowdown_percent: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Day04Config {
+    /// Let the words wrap around the edges of the grid
+    pub wrap: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Day11Config {
+    pub n_blinks: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Day13Config {
+    pub prize_offset: i64,
+    pub max_button_presses: i64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: 2024,
+            input_dir: PathBuf::from("src/inputs"),
+            answers: PathBuf::from("answers.toml"),
+            progress: PathBuf::from("progress.toml"),
+            archive_dir: PathBuf::from("puzzles"),
+            examples: PathBuf::from("src/inputs/examples.toml"),
+            timeout: 60,
+            jobs: 0,
+            recording: RecordingConfig::default(),
+            image: ImageConfig::default(),
+            bench: BenchConfig::default(),
+            day04: Day04Config::default(),
+            day11: Day11Config::default(),
+            day13: Day13Config::default(),
+            input: None,
+            repl: false,
+            record: None,
+            animate: false,
+            export: None,
+            force: false,
+            json: false,
+            explain: false,
+            args: vec![],
+            flags: vec![],
+            overrides: vec![],
+            root: PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+        }
+    }
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            frame_delay_ms: 100,
+            steps_per_frame: 1,
+            max_frames: 1000,
+        }
+    }
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self { cell_size: 8 }
+    }
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            history: PathBuf::from("bench_history.toml"),
+            repeats: 3,
+            slowdown_percent: 20.0,
+        }
+    }
+}
+
+impl Default for Day11Config {
+    fn default() -> Self {
+        Self { n_blinks: 75 }
+    }
+}
+
+impl Default for Day13Config {
+    fn default() -> Self {
+        Self {
+            prize_offset: 10000000000000,
+            max_button_presses: 100,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the configuration file and applies the overrides given in `args`.
+    pub fn from_args<I>(args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut config_path: Option<PathBuf> = None;
+        let mut input = None;
+        let mut repl = false;
+        let mut record = None;
+        let mut animate = false;
+        let mut export = None;
+        let mut force = false;
+        let mut json = false;
+        let mut explain = false;
+        let mut positional = vec![];
+        let mut flags = vec![];
+        let mut overrides: Vec<(String, String)> = vec![];
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            if arg.starts_with("--") {
+                flags.push(arg.clone());
+            }
+            let mut value = || match inline_value {
+                Some(value) => Ok(value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| ConfigError::MissingValue(flag.clone()))?;
+                    flags.push(value.clone());
+                    Ok(value)
+                }
+            };
+
+            match flag.as_str() {
+                "--config" => config_path = Some(PathBuf::from(value()?)),
+                "--input" => input = Some(PathBuf::from(value()?)),
+                "--repl" => repl = true,
+                "--record" => record = Some(PathBuf::from(value()?)),
+                "--animate" => animate = true,
+                "--export" => export = Some(PathBuf::from(value()?)),
+                "--force" => force = true,
+                "--json" => json = true,
+                "--explain" => explain = true,
+                "--year" => overrides.push(("year".to_string(), value()?)),
+                "--input-dir" => overrides.push(("input_dir".to_string(), quote(&value()?))),
+                "--answers" => overrides.push(("answers".to_string(), quote(&value()?))),
+                "--progress" => overrides.push(("progress".to_string(), quote(&value()?))),
+                "--timeout" => overrides.push(("timeout".to_string(), value()?)),
+                "--jobs" => overrides.push(("jobs".to_string(), value()?)),
+                "--set" => {
+                    let assignment = value()?;
+                    let (key, value) = assignment
+                        .split_once('=')
+                        .ok_or_else(|| ConfigError::InvalidOverride(assignment.clone()))?;
+                    overrides.push((key.trim().to_string(), value.trim().to_string()));
+                }
+                _ if !arg.starts_with("--") => positional.push(arg),
+                _ => return Err(ConfigError::UnknownFlag(arg)),
+            }
+        }
+
+        let default_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(CONFIG_FILE);
+        let (path, required) = match config_path {
+            Some(path) => (path, true),
+            None => (default_path, false),
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) if !required => String::new(),
+            Err(source) => return Err(ConfigError::Io { path, source }),
+        };
+
+        // parse the file on its own first, so errors point at the offending line
+        toml::from_str::<Config>(&text).map_err(|source| ConfigError::Parse {
+            path: path.clone(),
+            source,
+        })?;
+
+        let mut table: toml::Table = toml::from_str(&text).unwrap_or_default();
+        for (key, value) in overrides.iter() {
+            set_value(&mut table, key, value)?;
+        }
+
+        let mut config: Config = table.try_into().map_err(ConfigError::Override)?;
+        config.input = input;
+        config.repl = repl;
+        config.record = record;
+        config.animate = animate;
+        config.export = export;
+        config.force = force;
+        config.json = json;
+        config.explain = explain;
+        config.args = positional;
+        config.flags = flags;
+        config.overrides = overrides.into_iter().map(|(key, _)| key).collect();
+        config.root = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        Ok(config)
+    }
+
+    pub fn resolve(&self, path: &Path) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// Whether the answers of this run are answers to the puzzles, which they
+    /// are not with another input or changed settings of a day.
+    pub fn solves_puzzle(&self) -> bool {
+        self.input.is_none() && !self.overrides.iter().any(|key| key.starts_with("day"))
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        match &self.input {
+            Some(path) => path.clone(),
+            None => self
+                .resolve(&self.input_dir)
+                .join(format!("input_day{day:02}.txt")),
+        }
+    }
+
+    pub fn example_path(&self, day: u8) -> PathBuf {
+        self.resolve(&self.input_dir)
+            .join(format!("test_day{day:02}.txt"))
+    }
+
+    /// Expected rendered output of a day, see [`crate::snapshot`].
+    pub fn snapshot_path(&self, day: u8, name: &str) -> PathBuf {
+        self.resolve(&self.input_dir)
+            .join(format!("snapshot_day{day:02}_{name}.txt"))
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        self.resolve(&self.answers)
+    }
+
+    pub fn progress_path(&self) -> PathBuf {
+        self.resolve(&self.progress)
+    }
+
+    pub fn examples_path(&self) -> PathBuf {
+        self.resolve(&self.examples)
+    }
+
+    pub fn bench_history_path(&self) -> PathBuf {
+        self.resolve(&self.bench.history)
+    }
+
+    pub fn read_input(&self, day: u8) -> String {
+        crate::file_reader::get_input(&self.input_path(day).to_string_lossy())
+    }
+}
+
+/// Loads the configuration from the process arguments of a day binary,
+/// exiting with a message when it is invalid.
+pub fn load() -> Config {
+    match Config::from_args(std::env::args().skip(1)) {
+        Ok(config) if config.args.is_empty() => config,
+        Ok(config) => {
+            eprintln!("error: unexpected argument {}", config.args[0]);
+            std::process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn set_value(table: &mut toml::Table, key: &str, value: &str) -> Result<(), ConfigError> {
+    // values that are not valid toml (like a bare path) are taken as strings
+    let parsed = toml::from_str::<toml::Table>(&format!("value = {value}"))
+        .ok()
+        .and_then(|mut t| t.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()));
+
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().filter(|k| !k.is_empty());
+    let Some(last) = last else {
+        return Err(ConfigError::InvalidOverride(key.to_string()));
+    };
+
+    let mut current = table;
+    for part in parts {
+        current = current
+            .entry(part)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| ConfigError::InvalidOverride(key.to_string()))?;
+    }
+    current.insert(last.to_string(), parsed);
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    Override(toml::de::Error),
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidOverride(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            ConfigError::Parse { path, source } => {
+                write!(f, "invalid configuration in {}: {source}", path.display())
+            }
+            ConfigError::Override(source) => {
+                write!(f, "invalid command line override: {source}")
+            }
+            ConfigError::UnknownFlag(flag) => write!(f, "unknown flag {flag}"),
+            ConfigError::MissingValue(flag) => write!(f, "missing value for {flag}"),
+            ConfigError::InvalidOverride(s) => {
+                write!(f, "invalid override {s}, expected --set key=value")
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_overrides() {
+        let config = Config::from_args(args(&[
+            "--set",
+            "day11.n_blinks=25",
+            "--input-dir=other",
+            "--timeout",
+            "5",
+        ]))
+        .unwrap();
+        assert_eq!(config.day11.n_blinks, 25);
+        assert_eq!(config.input_dir, PathBuf::from("other"));
+        assert_eq!(config.timeout, 5);
+        assert!(!config.solves_puzzle());
+        assert_eq!(
+            config.flags,
+            args(&[
+                "--set",
+                "day11.n_blinks=25",
+                "--input-dir=other",
+                "--timeout",
+                "5"
+            ])
+        );
+        assert!(config.input_path(3).ends_with("other/input_day03.txt"));
+
+        let config = Config::from_args(args(&["--set", "recording.frame_delay_ms=5"])).unwrap();
+        assert!(config.solves_puzzle());
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let err = Config::from_args(args(&["--set", "day11.n_blink=25"])).unwrap_err();
+        assert!(err.to_string().contains("unknown field `n_blink`"));
+
+        let err = Config::from_args(args(&["--blinks", "25"])).unwrap_err();
+        assert!(matches!(err, ConfigError::UnknownFlag(_)));
+    }
+}
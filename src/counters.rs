@@ -0,0 +1,59 @@
+//! Named counters of the work a solution does.
+//!
+//! Counting the work, like the steps of the guard of day06 or the rule
+//! checks of day05, compares implementations independently of the machine
+//! they run on. A solution counts with [`add`] and [`increment`], and
+//! [`crate::runner::report`] reports the counters with the answer of each
+//! part, after which they start again from zero.
+//!
+//! Every increment takes a lock, so hot loops are better off counting in a
+//! local variable and adding it once at the end.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+static COUNTERS: Mutex<BTreeMap<&'static str, u64>> = Mutex::new(BTreeMap::new());
+
+/// Adds `n` to the counter called `name`.
+pub fn add(name: &'static str, n: u64) {
+    *COUNTERS.lock().unwrap().entry(name).or_insert(0) += n;
+}
+
+pub fn increment(name: &'static str) {
+    add(name, 1);
+}
+
+/// The counters so far, after which they are reset.
+pub fn take() -> BTreeMap<&'static str, u64> {
+    std::mem::take(&mut *COUNTERS.lock().unwrap())
+}
+
+/// The counters as `name: value` pairs on a single line.
+pub fn render(counters: &BTreeMap<&'static str, u64>) -> String {
+    counters
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters() {
+        // the counters are global, so only the names of this test are checked
+        add("test memo hits", 3);
+        increment("test memo hits");
+        increment("test bfs pops");
+
+        let counters = take();
+        assert_eq!(counters.get("test memo hits"), Some(&4));
+        assert_eq!(
+            render(&BTreeMap::from([("bfs pops", 1), ("memo hits", 4)])),
+            "bfs pops: 1, memo hits: 4"
+        );
+        assert_eq!(take().get("test memo hits"), None);
+    }
+}
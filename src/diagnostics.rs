@@ -0,0 +1,154 @@
+//! Errors pointing into the puzzle input.
+//!
+//! A [`ParseError`] keeps the offending line, so it can be displayed like a
+//! compiler error, with the line number and a caret under the exact span:
+//!
+//! ```text
+//! error: expected a number, found 'x'
+//!  --> src/inputs/input_day02.txt:3:5
+//!   |
+//! 3 | 7 6 x 2 1
+//!   |     ^
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    /// Number of characters to underline
+    pub width: usize,
+    pub message: String,
+    source_line: String,
+    origin: Option<String>,
+}
+
+impl ParseError {
+    /// An error at a line and column of `input`, underlining one character.
+    pub fn new(input: &str, line: usize, column: usize, message: impl Into<String>) -> Self {
+        let source_line = input
+            .lines()
+            .nth(line.saturating_sub(1))
+            .unwrap_or("")
+            .to_string();
+        Self {
+            line,
+            column,
+            width: 1,
+            message: message.into(),
+            source_line,
+            origin: None,
+        }
+    }
+
+    /// An error spanning `len` bytes from the byte `offset` in `input`,
+    /// for instance the range of a regex match.
+    pub fn at_offset(input: &str, offset: usize, len: usize, message: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+
+        let end = (offset + len).min(input.len());
+        let width = input[offset..end]
+            .split('\n')
+            .next()
+            .map_or(0, |s| s.chars().count());
+
+        Self::new(input, line, column, message).with_width(width)
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// Names the file the input came from, shown in the rendered error.
+    pub fn in_file(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line_nr = self.line.to_string();
+        let gutter = " ".repeat(line_nr.len());
+        let location = match &self.origin {
+            Some(origin) => format!("{origin}:{}:{}", self.line, self.column),
+            None => format!("line {}, column {}", self.line, self.column),
+        };
+        // tabs would shift the caret, so they are shown as single spaces
+        let source_line = self.source_line.replace('\t', " ");
+
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "{gutter}--> {location}")?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_nr} | {source_line}")?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column.saturating_sub(1)),
+            "^".repeat(self.width)
+        )
+    }
+}
+
+// shows the rendered error when a parse result is unwrapped
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\n{self}")
+    }
+}
+
+impl Error for ParseError {}
+
+/// The whitespace separated words of a line, with their 1-based columns.
+pub fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(char::is_whitespace)
+        .scan(1, |column, word| {
+            let start = *column;
+            *column += word.chars().count() + 1;
+            Some((start, word))
+        })
+        .filter(|(_, word)| !word.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let input = "7 6 4 2 1\n1 2 7 8 9\n7 6 x 2 1\n";
+        let error =
+            ParseError::new(input, 3, 5, "expected a number, found 'x'").in_file("input_day02.txt");
+        assert_eq!(
+            error.to_string(),
+            "error: expected a number, found 'x'\n \
+             --> input_day02.txt:3:5\n  \
+             |\n\
+             3 | 7 6 x 2 1\n  \
+             |     ^"
+        );
+    }
+
+    #[test]
+    fn test_words() {
+        let words: Vec<(usize, &str)> = words("3   4  x").collect();
+        assert_eq!(words, vec![(1, "3"), (5, "4"), (8, "x")]);
+    }
+
+    #[test]
+    fn test_at_offset() {
+        let input = "xmul(2,4)\n%&mul[3,7]!";
+        let error = ParseError::at_offset(input, 12, 4, "not an instruction");
+        assert_eq!((error.line, error.column, error.width), (2, 3, 4));
+        assert!(error.to_string().ends_with("2 | %&mul[3,7]!\n  |   ^^^^"));
+    }
+}
@@ -0,0 +1,170 @@
+//! The eight compass directions on a grid.
+//!
+//! North is up, so a step north lowers the row: the deltas are `(row, col)`
+//! offsets, ready for [`crate::grid::Grid::offset`]. The directions are
+//! ordered clockwise from north.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction; 8] = [N, NE, E, SE, S, SW, W, NW];
+    /// The directions along the rows and columns, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [N, E, S, W];
+    pub const DIAGONAL: [Direction; 4] = [NE, SE, SW, NW];
+
+    /// Every direction, clockwise from north.
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The direction `eighths` of a full turn clockwise from this one, or
+    /// counter-clockwise for negative `eighths`.
+    pub fn rotate(self, eighths: i32) -> Direction {
+        Self::ALL[(self.index() as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// A quarter turn counter-clockwise.
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// The `(row, col)` offset of a step in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            N => (-1, 0),
+            NE => (-1, 1),
+            E => (0, 1),
+            SE => (1, 1),
+            S => (1, 0),
+            SW => (1, -1),
+            W => (0, -1),
+            NW => (-1, -1),
+        }
+    }
+
+    /// The direction of an arrow `^>v<`, or of a letter `NESW`.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            '^' | 'N' => Some(N),
+            '>' | 'E' => Some(E),
+            'v' | 'S' => Some(S),
+            '<' | 'W' => Some(W),
+            _ => None,
+        }
+    }
+
+    /// The arrow `^>v<` pointing this way, for the cardinal directions.
+    pub fn arrow(self) -> Option<char> {
+        match self {
+            N => Some('^'),
+            E => Some('>'),
+            S => Some('v'),
+            W => Some('<'),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownDirection(pub String);
+
+impl fmt::Display for UnknownDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown direction {:?}, expected one of ^>v< or N, NE, ..., NW",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownDirection {}
+
+/// Parses the name of a direction like `N` or `SW`, or an arrow like `^`.
+impl FromStr for Direction {
+    type Err = UnknownDirection;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if let Some(direction) = Direction::from_char(c) {
+                return Ok(direction);
+            }
+        }
+        Direction::iter()
+            .find(|direction| direction.to_string() == s)
+            .ok_or_else(|| UnknownDirection(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(N.turn_right(), E);
+        assert_eq!(N.turn_left(), W);
+        assert_eq!(NE.turn_right(), SE);
+        assert_eq!(SW.reverse(), NE);
+        assert_eq!(W.rotate(1), NW);
+        assert_eq!(N.rotate(-9), NW);
+        for direction in Direction::iter() {
+            let (dr, dc) = direction.delta();
+            assert_eq!(direction.reverse().delta(), (-dr, -dc));
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.is_diagonal(), dr != 0 && dc != 0);
+        }
+        assert_eq!(Direction::iter().filter(|d| d.is_diagonal()).count(), 4);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Direction::from_char('v'), Some(S));
+        assert_eq!("<".parse(), Ok(W));
+        assert_eq!("E".parse(), Ok(E));
+        assert_eq!("NW".parse(), Ok(NW));
+        assert!("up".parse::<Direction>().is_err());
+        assert_eq!(E.arrow(), Some('>'));
+        assert_eq!(SE.arrow(), None);
+    }
+}
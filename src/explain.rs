@@ -0,0 +1,96 @@
+//! Traces of how the answers are derived.
+//!
+//! A day started with `--explain` reports the intermediate steps of its
+//! solution, like the pairs of day01 or the button presses of day13. Each
+//! step is printed as a line of text, or as a JSON object per line with
+//! `--json`:
+//!
+//! ```text
+//! explain part 1 pair: left=3 right=7 distance=4
+//! {"day":1,"part":1,"step":"pair","left":3,"right":7,"distance":4}
+//! ```
+
+use serde_json::{Map, Value};
+
+use crate::config::Config;
+
+#[derive(Debug, Clone)]
+pub struct Explain {
+    day: u8,
+    enabled: bool,
+    json: bool,
+}
+
+impl Explain {
+    pub fn new(config: &Config, day: u8) -> Self {
+        Self {
+            day,
+            enabled: config.explain,
+            json: config.json,
+        }
+    }
+
+    /// Whether steps are printed, to skip work that only feeds the trace.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Prints a step of `part`, with the fields of the `details` object.
+    pub fn step(&self, part: u8, step: &str, details: Value) {
+        if self.enabled {
+            println!("{}", self.format(part, step, details));
+        }
+    }
+
+    fn format(&self, part: u8, step: &str, details: Value) -> String {
+        let fields = match details {
+            Value::Object(fields) => fields,
+            Value::Null => Map::new(),
+            value => Map::from_iter([(String::from("value"), value)]),
+        };
+
+        if self.json {
+            let mut line = Map::new();
+            line.insert(String::from("day"), self.day.into());
+            line.insert(String::from("part"), part.into());
+            line.insert(String::from("step"), step.into());
+            line.extend(fields);
+            return Value::Object(line).to_string();
+        }
+
+        let fields: Vec<String> = fields
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(text) => format!("{key}={text}"),
+                value => format!("{key}={value}"),
+            })
+            .collect();
+        format!("explain part {part} {step}: {}", fields.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format() {
+        let mut explain = Explain {
+            day: 1,
+            enabled: true,
+            json: false,
+        };
+        let details = json!({"left": 3, "right": 7, "distance": 4, "note": "ok", "pair": [3, 7]});
+        assert_eq!(
+            explain.format(1, "pair", details.clone()),
+            "explain part 1 pair: left=3 right=7 distance=4 note=ok pair=[3,7]"
+        );
+
+        explain.json = true;
+        assert_eq!(
+            explain.format(1, "pair", details),
+            r#"{"day":1,"part":1,"step":"pair","left":3,"right":7,"distance":4,"note":"ok","pair":[3,7]}"#
+        );
+    }
+}
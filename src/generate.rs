@@ -0,0 +1,399 @@
+//! Synthetic puzzle inputs.
+//!
+//! Every day has a generator producing an input in the exact format of the
+//! puzzle. The output only depends on the seed and the size, so an input can
+//! be reproduced from the two numbers, e.g. `aoc generate 6 42 100`. What
+//! the size means differs per day: the number of lines, the side of a grid,
+//! the number of machines, ...
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// A small SplitMix64 generator, so generated inputs never change between
+/// versions of a dependency.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low..=high`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low + 1)
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0, len as u64 - 1) as usize
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(1, 100) <= percent
+    }
+
+    pub fn choose<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+pub const DAYS: std::ops::RangeInclusive<u8> = 1..=13;
+
+/// Generates an input for `day`, or `None` if the day has no generator.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let mut rng = Rng::new(seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => location_lists(&mut rng, size),
+        2 => reports(&mut rng, size),
+        3 => corrupted_memory(&mut rng, size),
+        4 => letter_grid(&mut rng, size, &['X', 'M', 'A', 'S']),
+        5 => page_rules(&mut rng, size),
+        6 => lab_map(&mut rng, size),
+        7 => equations(&mut rng, size),
+        8 => antenna_map(&mut rng, size),
+        9 => disk_map(&mut rng, size),
+        10 => topographic_map(&mut rng, size),
+        11 => stones(&mut rng, size),
+        12 => garden(&mut rng, size),
+        13 => claw_machines(&mut rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn location_lists(rng: &mut Rng, n_lines: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..n_lines {
+        writeln!(
+            out,
+            "{}   {}",
+            rng.range(10000, 99999),
+            rng.range(10000, 99999)
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn reports(rng: &mut Rng, n_reports: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..n_reports {
+        let len = rng.range(5, 8) as usize;
+        let mut levels: Vec<i64> = vec![rng.range(10, 90) as i64];
+        let direction = if rng.chance(50) { 1 } else { -1 };
+        let safe = rng.chance(50);
+        for _ in 1..len {
+            let step = match safe {
+                true => direction * rng.range(1, 3) as i64,
+                false => rng.range(0, 8) as i64 - 4,
+            };
+            levels.push((levels.last().unwrap() + step).max(1));
+        }
+        let line: Vec<String> = levels.iter().map(|l| l.to_string()).collect();
+        writeln!(out, "{}", line.join(" ")).unwrap();
+    }
+    out
+}
+
+fn corrupted_memory(rng: &mut Rng, n_chunks: usize) -> String {
+    const JUNK: &[char] = &[
+        '%', '&', '!', '@', '^', '*', '[', ']', '(', ')', '<', '>', '?', ',', ' ', '+', '-', '\'',
+        '{', '}', 'x', 'w', 'h', 'y', 'o', 'm', 'u', 'l',
+    ];
+    let mut out = String::new();
+    for _ in 0..n_chunks {
+        for _ in 0..rng.range(0, 6) {
+            out.push(rng.choose(JUNK));
+        }
+        let (a, b) = (rng.range(1, 999), rng.range(1, 999));
+        match rng.range(0, 9) {
+            0..=4 => write!(out, "mul({a},{b})").unwrap(),
+            5 => out.push_str("do()"),
+            6 => out.push_str("don't()"),
+            7 => write!(out, "mul[{a},{b}]").unwrap(),
+            8 => write!(out, "mul({a}, {b})").unwrap(),
+            _ => write!(out, "mul({a}*").unwrap(),
+        }
+    }
+    out.push('\n');
+    out
+}
+
+fn letter_grid(rng: &mut Rng, side: usize, letters: &[char]) -> String {
+    let mut out = String::new();
+    for _ in 0..side {
+        for _ in 0..side {
+            out.push(rng.choose(letters));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn page_rules(rng: &mut Rng, n_updates: usize) -> String {
+    // a hidden order of the pages, every pair of pages gets a rule
+    let mut pages: Vec<u64> = (10..100).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate(5 + n_updates.min(40));
+
+    let mut rules = vec![];
+    for (i, before) in pages.iter().enumerate() {
+        for after in pages[i + 1..].iter() {
+            rules.push(format!("{before}|{after}"));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    let mut out = rules.join("\n");
+    out.push_str("\n\n");
+    for _ in 0..n_updates {
+        let mut update = pages.clone();
+        rng.shuffle(&mut update);
+        let max_len = update.len().min(23);
+        let len = 2 * rng.range(1, (max_len as u64 - 1) / 2) as usize + 1;
+        update.truncate(len);
+        // about half of the updates are in the right order
+        if rng.chance(50) {
+            update.sort_by_key(|page| pages.iter().position(|p| p == page));
+        }
+        let update: Vec<String> = update.iter().map(|p| p.to_string()).collect();
+        writeln!(out, "{}", update.join(",")).unwrap();
+    }
+    out
+}
+
+fn lab_map(rng: &mut Rng, side: usize) -> String {
+    let side = side.max(2);
+    // retry until the guard walks off the map, as it does in the puzzle
+    loop {
+        let mut map: Vec<Vec<char>> = (0..side)
+            .map(|_| {
+                (0..side)
+                    .map(|_| if rng.chance(10) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        let guard = (rng.index(side), rng.index(side));
+        map[guard.0][guard.1] = '^';
+
+        if guard_leaves(&map, guard) {
+            return map
+                .iter()
+                .map(|row| row.iter().collect::<String>() + "\n")
+                .collect();
+        }
+    }
+}
+
+fn guard_leaves(map: &[Vec<char>], start: (usize, usize)) -> bool {
+    let directions: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+    let mut seen = HashSet::new();
+    let (mut position, mut direction) = (start, 0);
+    while seen.insert((position, direction)) {
+        let (dr, dc) = directions[direction];
+        let (row, col) = (position.0 as isize + dr, position.1 as isize + dc);
+        if row < 0 || col < 0 || row as usize >= map.len() || col as usize >= map[0].len() {
+            return true;
+        }
+        match map[row as usize][col as usize] {
+            '#' => direction = (direction + 1) % 4,
+            _ => position = (row as usize, col as usize),
+        }
+    }
+    false
+}
+
+fn equations(rng: &mut Rng, n_equations: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..n_equations {
+        let numbers: Vec<u64> = (0..rng.range(2, 6))
+            .map(|_| match rng.chance(80) {
+                true => rng.range(1, 99),
+                false => rng.range(100, 999),
+            })
+            .collect();
+
+        // about half of the equations can be made true
+        let test_value = match rng.chance(50) {
+            true => numbers[1..]
+                .iter()
+                .fold(numbers[0], |acc, &x| match rng.range(0, 2) {
+                    0 => acc + x,
+                    1 => acc * x,
+                    _ => format!("{acc}{x}").parse().unwrap(),
+                }),
+            false => rng.range(1, 10u64.pow(numbers.len() as u32 + 1)),
+        };
+        let numbers: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+        writeln!(out, "{test_value}: {}", numbers.join(" ")).unwrap();
+    }
+    out
+}
+
+fn antenna_map(rng: &mut Rng, side: usize) -> String {
+    let frequencies: Vec<char> = ('a'..='z').chain('A'..='Z').chain('0'..='9').collect();
+    let in_use: Vec<char> = (0..rng.range(1, 6))
+        .map(|_| rng.choose(&frequencies))
+        .collect();
+    let mut out = String::new();
+    for _ in 0..side {
+        for _ in 0..side {
+            out.push(match rng.chance(5) {
+                true => rng.choose(&in_use),
+                false => '.',
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn disk_map(rng: &mut Rng, n_files: usize) -> String {
+    let mut out = String::new();
+    for i in 0..n_files {
+        if i > 0 {
+            out.push_str(&rng.range(0, 9).to_string());
+        }
+        out.push_str(&rng.range(1, 9).to_string());
+    }
+    out.push('\n');
+    out
+}
+
+fn topographic_map(rng: &mut Rng, side: usize) -> String {
+    let side = side.max(2);
+    let mut map: Vec<Vec<u64>> = (0..side)
+        .map(|_| (0..side).map(|_| rng.range(0, 9)).collect())
+        .collect();
+
+    // carve some hiking trails, so there are trailheads to find
+    for _ in 0..side.div_ceil(2) {
+        let mut trail = vec![(rng.index(side), rng.index(side))];
+        while trail.len() < 10 {
+            let (row, col) = *trail.last().unwrap();
+            let neighbors: Vec<(usize, usize)> = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ]
+            .into_iter()
+            .filter(|&(r, c)| r < side && c < side && !trail.contains(&(r, c)))
+            .collect();
+            if neighbors.is_empty() {
+                break;
+            }
+            trail.push(rng.choose(&neighbors));
+        }
+        for (height, &(row, col)) in trail.iter().enumerate() {
+            map[row][col] = height as u64;
+        }
+    }
+
+    map.iter()
+        .map(|row| row.iter().map(|h| h.to_string()).collect::<String>() + "\n")
+        .collect()
+}
+
+fn stones(rng: &mut Rng, n_stones: usize) -> String {
+    let stones: Vec<String> = (0..n_stones)
+        .map(|_| match rng.chance(10) {
+            true => 0,
+            false => rng.range(1, 999999),
+        })
+        .map(|s| s.to_string())
+        .collect();
+    stones.join(" ") + "\n"
+}
+
+fn garden(rng: &mut Rng, side: usize) -> String {
+    let plants: Vec<char> = ('A'..='Z').take(rng.range(2, 26) as usize).collect();
+    let mut map: Vec<Vec<char>> = vec![];
+    // plants mostly continue the region above or to the left of them
+    for row in 0..side {
+        let mut line = vec![];
+        for col in 0..side {
+            let plant = match rng.range(0, 9) {
+                0..=3 if col > 0 => line[col - 1],
+                4..=7 if row > 0 => map[row - 1][col],
+                _ => rng.choose(&plants),
+            };
+            line.push(plant);
+        }
+        map.push(line);
+    }
+    map.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+fn claw_machines(rng: &mut Rng, n_machines: usize) -> String {
+    let mut machines = vec![];
+    for _ in 0..n_machines {
+        let a = (rng.range(10, 99), rng.range(10, 99));
+        let mut b = (rng.range(10, 99), rng.range(10, 99));
+        // like in the puzzle, the buttons never move the claw in the same direction
+        while a.0 * b.1 == a.1 * b.0 {
+            b = (rng.range(10, 99), rng.range(10, 99));
+        }
+        // about half of the prizes can be won
+        let prize = match rng.chance(50) {
+            true => {
+                let (presses_a, presses_b) = (rng.range(0, 100), rng.range(0, 100));
+                (
+                    a.0 * presses_a + b.0 * presses_b,
+                    a.1 * presses_a + b.1 * presses_b,
+                )
+            }
+            false => (rng.range(1000, 20000), rng.range(1000, 20000)),
+        };
+        machines.push(format!(
+            "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+            a.0, a.1, b.0, b.1, prize.0, prize.1
+        ));
+    }
+    machines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint;
+
+    #[test]
+    fn test_generated_inputs_are_valid() {
+        for day in DAYS {
+            for seed in 0..20 {
+                let input = generate(day, seed, 1 + seed as usize).unwrap();
+                let diagnostics = lint::validator(day).unwrap().check(&input);
+                assert!(
+                    diagnostics.is_empty(),
+                    "day {day} seed {seed}: {diagnostics:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        assert_eq!(generate(4, 7, 10), generate(4, 7, 10));
+        assert_ne!(generate(4, 7, 10), generate(4, 8, 10));
+    }
+}
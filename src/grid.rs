@@ -0,0 +1,296 @@
+//! Rectangular grids of cells, parsed from the puzzle inputs.
+//!
+//! Most puzzles are a map with one character per cell. [`Grid::parse`] turns
+//! the characters into cells with a mapping function and rejects ragged
+//! lines with a [`ParseError`]. Positions are `(row, col)` pairs; moving by
+//! a signed offset is bounds checked, so stepping off the map gives `None`
+//! instead of wrapping around.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::diagnostics::ParseError;
+use crate::direction::Direction;
+use crate::point::Point;
+use crate::wrapping::WrappingGrid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid of `rows` by `cols` cells, with the value of `cell(row, col)`.
+    pub fn from_fn<F>(rows: usize, cols: usize, mut cell: F) -> Self
+    where
+        F: FnMut(usize, usize) -> T,
+    {
+        let cells = (0..rows * cols).map(|i| cell(i / cols, i % cols)).collect();
+        Grid { rows, cols, cells }
+    }
+
+    /// Parses a grid with a cell per character. `cell` maps the characters,
+    /// returning `None` for characters that don't belong in the grid.
+    pub fn parse<F>(input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = vec![];
+        let mut cols = None;
+        let mut rows = 0;
+        for (i, line) in input.lines().enumerate() {
+            let before = cells.len();
+            for (j, c) in line.chars().enumerate() {
+                match cell(c) {
+                    Some(value) => cells.push(value),
+                    None => {
+                        return Err(ParseError::new(
+                            input,
+                            i + 1,
+                            j + 1,
+                            format!("unexpected {c:?} in the grid"),
+                        ))
+                    }
+                }
+            }
+            let len = cells.len() - before;
+            match cols {
+                None => cols = Some(len),
+                Some(cols) if cols != len => {
+                    return Err(ParseError::new(
+                        input,
+                        i + 1,
+                        cols.min(len) + 1,
+                        format!("line has {len} cells, but the first line has {cols}"),
+                    ));
+                }
+                Some(_) => {}
+            }
+            rows += 1;
+        }
+        match cols {
+            Some(cols) if cols > 0 => Ok(Grid { rows, cols, cells }),
+            _ => Err(ParseError::new(input, 1, 1, "the grid is empty")),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[position.0 * self.cols + position.1])
+    }
+
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        match self.contains(position) {
+            true => Some(&mut self.cells[position.0 * self.cols + position.1]),
+            false => None,
+        }
+    }
+
+    /// The position `offset` away from `position`, if it is on the grid.
+    pub fn offset(
+        &self,
+        (row, col): (usize, usize),
+        (dr, dc): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let position = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+        self.contains(position).then_some(position)
+    }
+
+    /// The position a step in `direction` away, if it is on the grid.
+    pub fn step(&self, position: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        self.offset(position, direction.delta())
+    }
+
+    /// The cell `offset` away from `position`, if it is on the grid.
+    pub fn get_offset(&self, position: (usize, usize), offset: (isize, isize)) -> Option<&T> {
+        self.get(self.offset(position, offset)?)
+    }
+
+    /// The position of `point`, if it is on the grid.
+    pub fn index_of(&self, point: Point) -> Option<(usize, usize)> {
+        point.to_index().filter(|&position| self.contains(position))
+    }
+
+    pub fn get_point(&self, point: Point) -> Option<&T> {
+        self.get(self.index_of(point)?)
+    }
+
+    /// The positions of the cells sharing a side with `position`.
+    pub fn neighbors4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .filter_map(move |direction| self.step(position, direction))
+    }
+
+    /// The positions of the cells sharing a side or a corner with `position`.
+    pub fn neighbors8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::iter().filter_map(move |direction| self.step(position, direction))
+    }
+
+    /// Every position with its cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let cols = self.cols;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / cols, i % cols), cell))
+    }
+
+    /// The positions of the cells matching `predicate`, row by row.
+    pub fn positions<'a, P>(&'a self, predicate: P) -> impl Iterator<Item = (usize, usize)> + 'a
+    where
+        P: Fn(&T) -> bool + 'a,
+    {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// A view of the grid that wraps around the edges, see [`WrappingGrid`].
+    pub fn wrapping(&self) -> WrappingGrid<'_, T> {
+        WrappingGrid::new(self)
+    }
+
+    /// A grid of the same size with every cell mapped by `f`.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid of `rows` by `cols` copies of `value`.
+    pub fn filled(rows: usize, cols: usize, value: T) -> Self {
+        Grid {
+            rows,
+            cols,
+            cells: vec![value; rows * cols],
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &T {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "position {position:?} is outside of the {}x{} grid",
+                self.rows, self.cols
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("position {position:?} is outside of the {rows}x{cols} grid"))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get_point(point).unwrap_or_else(|| {
+            panic!(
+                "point {point} is outside of the {}x{} grid",
+                self.rows, self.cols
+            )
+        })
+    }
+}
+
+/// Rows of cells without separators, like the puzzle input.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            for cell in self.row(row) {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("0123\n4567\n", |c| c.to_digit(10)).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 4));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.to_string(), "0123\n4567\n");
+        assert_eq!(
+            grid.positions(|&d| d % 3 == 0).collect::<Vec<_>>(),
+            [(0, 0), (0, 3), (1, 2)]
+        );
+
+        let err = Grid::parse("0123\n456\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.message, "line has 3 cells, but the first line has 4");
+
+        let err = Grid::parse("01\n2x\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(Grid::parse("", Some).is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::from_fn(3, 3, |row, col| row * 3 + col);
+        assert_eq!(grid.get_offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.get_offset((0, 0), (2, 1)), Some(&7));
+        assert_eq!(grid.get((3, 0)), None);
+
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbors8((0, 2)).collect::<Vec<_>>(),
+            [(1, 2), (1, 1), (0, 1)]
+        );
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+
+        assert_eq!(grid.index_of(Point::new(2, 1)), Some((1, 2)));
+        assert_eq!(grid.get_point(Point::new(-1, 0)), None);
+        assert_eq!(grid.get_point(Point::new(0, 3)), None);
+        assert_eq!(grid[Point::new(1, 2)], 7);
+    }
+}
@@ -0,0 +1,327 @@
+//! Pictures of grid states, written as SVG or binary PPM.
+//!
+//! A day builds a [`Picture`] from a function giving the color of every
+//! cell, optionally draws paths and markers on top, and saves it when it is
+//! started with `--export out.svg` (or `out.ppm`). Cells are
+//! `image.cell_size` pixels wide.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 40, 40);
+    pub const GRAY: Rgb = Rgb(90, 90, 90);
+
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// A color for every index, neighbouring indices get clearly different hues.
+pub fn palette(index: usize) -> Rgb {
+    // steps of the golden angle spread the hues evenly
+    let hue = (index as f64 * 137.508) % 360.0;
+    let lightness = [0.55, 0.7, 0.4][index % 3];
+    hsl(hue, 0.65, lightness)
+}
+
+/// A color from dark blue for 0.0 over green and yellow to white for 1.0.
+pub fn gradient(value: f64) -> Rgb {
+    const STOPS: [(f64, Rgb); 4] = [
+        (0.0, Rgb(20, 30, 110)),
+        (0.4, Rgb(40, 150, 70)),
+        (0.75, Rgb(230, 210, 80)),
+        (1.0, Rgb(255, 255, 255)),
+    ];
+    let value = value.clamp(0.0, 1.0);
+    let upper = STOPS
+        .iter()
+        .position(|&(at, _)| at >= value)
+        .unwrap_or(3)
+        .max(1);
+    let ((from_at, from), (to_at, to)) = (STOPS[upper - 1], STOPS[upper]);
+    let t = (value - from_at) / (to_at - from_at);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+fn hsl(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+    Rgb(channel(r), channel(g), channel(b))
+}
+
+#[derive(Debug, Clone)]
+pub struct Picture {
+    rows: usize,
+    cols: usize,
+    cells: Vec<Rgb>,
+    paths: Vec<(Vec<(usize, usize)>, Rgb)>,
+    markers: Vec<((usize, usize), Rgb)>,
+}
+
+impl Picture {
+    /// A picture of `rows` by `cols` cells, colored by `color(row, col)`.
+    pub fn new<F>(rows: usize, cols: usize, color: F) -> Self
+    where
+        F: Fn(usize, usize) -> Rgb,
+    {
+        let cells = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .map(|(row, col)| color(row, col))
+            .collect();
+        Picture {
+            rows,
+            cols,
+            cells,
+            paths: vec![],
+            markers: vec![],
+        }
+    }
+
+    /// Draws a line through the centers of the cells.
+    pub fn path(&mut self, cells: Vec<(usize, usize)>, color: Rgb) {
+        self.paths.push((cells, color));
+    }
+
+    /// Draws a dot in the center of the cell.
+    pub fn marker(&mut self, cell: (usize, usize), color: Rgb) {
+        self.markers.push((cell, color));
+    }
+
+    fn center(cell: (usize, usize), cell_size: usize) -> (f64, f64) {
+        let half = cell_size as f64 / 2.0;
+        (
+            (cell.1 * cell_size) as f64 + half,
+            (cell.0 * cell_size) as f64 + half,
+        )
+    }
+
+    pub fn to_svg(&self, cell_size: usize) -> String {
+        let (width, height) = (self.cols * cell_size, self.rows * cell_size);
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+        )
+        .unwrap();
+
+        // runs of equal cells become a single rectangle
+        for (row, line) in self.cells.chunks(self.cols.max(1)).enumerate() {
+            let mut col = 0;
+            for run in line.chunk_by(|a, b| a == b) {
+                writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{cell_size}" fill="{}"/>"#,
+                    col * cell_size,
+                    row * cell_size,
+                    run.len() * cell_size,
+                    run[0].hex()
+                )
+                .unwrap();
+                col += run.len();
+            }
+        }
+
+        let stroke = (cell_size as f64 / 4.0).max(1.0);
+        for (cells, color) in self.paths.iter() {
+            let points: Vec<String> = cells
+                .iter()
+                .map(|&cell| {
+                    let (x, y) = Self::center(cell, cell_size);
+                    format!("{x},{y}")
+                })
+                .collect();
+            writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{stroke}" stroke-linecap="round" stroke-linejoin="round" shape-rendering="auto"/>"#,
+                points.join(" "),
+                color.hex()
+            )
+            .unwrap();
+        }
+
+        for &(cell, color) in self.markers.iter() {
+            let (x, y) = Self::center(cell, cell_size);
+            writeln!(
+                svg,
+                r#"<circle cx="{x}" cy="{y}" r="{}" fill="{}" shape-rendering="auto"/>"#,
+                cell_size as f64 / 3.0,
+                color.hex()
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// A binary (P6) PPM image.
+    pub fn to_ppm(&self, cell_size: usize) -> Vec<u8> {
+        let (width, height) = (self.cols * cell_size, self.rows * cell_size);
+        let mut pixels: Vec<Rgb> = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(self.cells[y / cell_size * self.cols + x / cell_size]);
+            }
+        }
+
+        let mut dot = |(cx, cy): (f64, f64), radius: f64, color: Rgb| {
+            let (x_min, x_max) = ((cx - radius).floor().max(0.0), (cx + radius).ceil());
+            let (y_min, y_max) = ((cy - radius).floor().max(0.0), (cy + radius).ceil());
+            for y in y_min as usize..(y_max as usize).min(height) {
+                for x in x_min as usize..(x_max as usize).min(width) {
+                    let (dx, dy) = (x as f64 + 0.5 - cx, y as f64 + 0.5 - cy);
+                    if dx * dx + dy * dy <= radius * radius {
+                        pixels[y * width + x] = color;
+                    }
+                }
+            }
+        };
+
+        // lines are drawn as dots along the way, every half pixel
+        let stroke = (cell_size as f64 / 8.0).max(0.5);
+        for (cells, color) in self.paths.iter() {
+            for pair in cells.windows(2) {
+                let (from, to) = (
+                    Self::center(pair[0], cell_size),
+                    Self::center(pair[1], cell_size),
+                );
+                let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+                let n_dots = (length * 2.0).ceil().max(1.0) as usize;
+                for i in 0..=n_dots {
+                    let t = i as f64 / n_dots as f64;
+                    let point = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+                    dot(point, stroke, *color);
+                }
+            }
+        }
+        for &(cell, color) in self.markers.iter() {
+            dot(Self::center(cell, cell_size), cell_size as f64 / 3.0, color);
+        }
+
+        let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+        for pixel in pixels {
+            ppm.extend([pixel.0, pixel.1, pixel.2]);
+        }
+        ppm
+    }
+
+    /// Saves the picture in the format given by the extension of `path`.
+    pub fn save(&self, path: &Path, cell_size: usize) -> Result<(), ImageError> {
+        let cell_size = cell_size.max(1);
+        let bytes = match path.extension().and_then(|e| e.to_str()) {
+            Some("svg") => self.to_svg(cell_size).into_bytes(),
+            Some("ppm") => self.to_ppm(cell_size),
+            _ => return Err(ImageError::UnknownFormat(path.to_path_buf())),
+        };
+        fs::write(path, bytes).map_err(|source| ImageError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Saves the picture with the cell size of the configuration, exiting with a
+/// message when that fails.
+pub fn export_or_exit(config: &Config, path: &Path, picture: &Picture) {
+    if let Err(e) = picture.save(path, config.image.cell_size) {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+    println!("exported the grid to {}", path.display());
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    UnknownFormat(PathBuf),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Io { path, source } => {
+                write!(f, "could not write {}: {source}", path.display())
+            }
+            ImageError::UnknownFormat(path) => {
+                write!(f, "can't export {}, use .svg or .ppm", path.display())
+            }
+        }
+    }
+}
+
+impl Error for ImageError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Picture {
+        Picture::new(2, 3, |row, col| match (row + col) % 2 {
+            0 => Rgb::BLACK,
+            _ => Rgb::WHITE,
+        })
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut picture = checkerboard();
+        let ppm = picture.to_ppm(1);
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(
+            &ppm[11..],
+            &[0, 0, 0, 255, 255, 255, 0, 0, 0, 255, 255, 255, 0, 0, 0, 255, 255, 255]
+        );
+
+        // a marker covers the center of its cell
+        picture.marker((1, 2), Rgb::RED);
+        let ppm = picture.to_ppm(9);
+        let header_len = "P6\n27 18\n255\n".len();
+        let center = header_len + 3 * (13 * 27 + 22);
+        assert_eq!(&ppm[center..center + 3], &[220, 40, 40]);
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut picture = Picture::new(1, 4, |_, col| if col < 3 { Rgb::WHITE } else { Rgb::GRAY });
+        picture.path(vec![(0, 0), (0, 3)], Rgb::RED);
+        let svg = picture.to_svg(10);
+
+        assert!(svg.contains(r##"<rect x="0" y="0" width="30" height="10" fill="#ffffff"/>"##));
+        assert!(svg.contains(r##"<rect x="30" y="0" width="10" height="10" fill="#5a5a5a"/>"##));
+        assert!(svg.contains(r#"points="5,5 35,5""#));
+    }
+
+    #[test]
+    fn test_colors() {
+        assert_eq!(gradient(0.0), Rgb(20, 30, 110));
+        assert_eq!(gradient(1.0), Rgb::WHITE);
+        assert_ne!(palette(0), palette(1));
+        assert!(checkerboard()
+            .save(Path::new("grid.png"), 4)
+            .is_err_and(|e| matches!(e, ImageError::UnknownFormat(_))));
+    }
+}
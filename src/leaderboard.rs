@@ -0,0 +1,346 @@
+//! Analysis of an exported private leaderboard.
+//!
+//! Reads the JSON of a private leaderboard (as served by the site's
+//! `.../leaderboard/private/view/<id>.json`) and reports the star times of
+//! every member, the time between part one and part two, the rankings per
+//! day and streaks. Times are counted from the release of the puzzle, at
+//! midnight EST.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: BTreeMap<String, Member>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub stars: u32,
+    #[serde(default)]
+    pub local_score: u32,
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub event: String,
+    pub members: Vec<MemberReport>,
+    pub days: Vec<DayRanking>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberReport {
+    pub name: String,
+    pub stars: u32,
+    pub local_score: u32,
+    pub days: Vec<MemberDay>,
+    /// Most consecutive days with both stars on the day of release
+    pub longest_streak: u32,
+    /// Streak ending at the last released day of the leaderboard
+    pub current_streak: u32,
+}
+
+/// Times in seconds since the release of the puzzle.
+#[derive(Debug, Serialize)]
+pub struct MemberDay {
+    pub day: u8,
+    pub part1: Option<i64>,
+    pub part2: Option<i64>,
+    pub part2_delta: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayRanking {
+    pub day: u8,
+    pub ranking: Vec<RankEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RankEntry {
+    pub rank: usize,
+    pub name: String,
+    pub part1: Option<i64>,
+    pub part2: Option<i64>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    fn star_ts(&self, day: u8, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(&part.to_string())
+            .map(|star| star.get_star_ts)
+    }
+}
+
+pub fn parse(json: &str) -> Result<Leaderboard, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+/// Unix time of the release of a puzzle: 05:00 UTC on the day in December.
+pub fn release_time(year: i64, day: u8) -> i64 {
+    days_from_civil(year, 12, day as u32) * 86400 + 5 * 3600
+}
+
+/// Days since 1970-01-01, following Howard Hinnant's `days_from_civil`.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let day_of_year = (153 * mp + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Analyzes the leaderboard of an event, which is `default_year` when the
+/// export doesn't name a year.
+pub fn analyze(leaderboard: &Leaderboard, default_year: u16) -> Report {
+    let year: i64 = leaderboard.event.parse().unwrap_or(default_year as i64);
+    let members: Vec<&Member> = leaderboard.members.values().collect();
+
+    let last_day = members
+        .iter()
+        .flat_map(|m| m.completion_day_level.keys())
+        .filter_map(|day| day.parse::<u8>().ok())
+        .max()
+        .unwrap_or(0);
+
+    let since_release = |member: &Member, day: u8, part: u8| -> Option<i64> {
+        member
+            .star_ts(day, part)
+            .map(|ts| ts - release_time(year, day))
+    };
+
+    let mut member_reports = vec![];
+    for member in members.iter() {
+        let mut days = vec![];
+        let mut streak = 0;
+        let mut longest_streak = 0;
+
+        for day in 1..=last_day {
+            let part1 = since_release(member, day, 1);
+            let part2 = since_release(member, day, 2);
+            let part2_delta = part1.zip(part2).map(|(a, b)| b - a);
+
+            if part2.is_some_and(|t| t < 86400) {
+                streak += 1;
+                longest_streak = longest_streak.max(streak);
+            } else {
+                streak = 0;
+            }
+
+            if part1.is_some() {
+                days.push(MemberDay {
+                    day,
+                    part1,
+                    part2,
+                    part2_delta,
+                });
+            }
+        }
+
+        member_reports.push(MemberReport {
+            name: member.display_name(),
+            stars: member.stars,
+            local_score: member.local_score,
+            days,
+            longest_streak,
+            current_streak: streak,
+        });
+    }
+    member_reports.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then(b.stars.cmp(&a.stars))
+            .then(a.name.cmp(&b.name))
+    });
+
+    let mut rankings = vec![];
+    for day in 1..=last_day {
+        let mut entries: Vec<(Option<i64>, Option<i64>, String)> = members
+            .iter()
+            .filter_map(|m| {
+                let part1 = since_release(m, day, 1)?;
+                Some((Some(part1), since_release(m, day, 2), m.display_name()))
+            })
+            .collect();
+
+        // both stars rank before one star, then the fastest first
+        entries.sort_by_key(|(part1, part2, name)| (part2.is_none(), *part2, *part1, name.clone()));
+
+        rankings.push(DayRanking {
+            day,
+            ranking: entries
+                .into_iter()
+                .enumerate()
+                .map(|(i, (part1, part2, name))| RankEntry {
+                    rank: i + 1,
+                    name,
+                    part1,
+                    part2,
+                })
+                .collect(),
+        });
+    }
+
+    Report {
+        event: leaderboard.event.clone(),
+        members: member_reports,
+        days: rankings,
+    }
+}
+
+/// Formats a number of seconds like `01:02:03`, with days prepended if needed.
+pub fn format_duration(seconds: Option<i64>) -> String {
+    let Some(seconds) = seconds else {
+        return String::from("-");
+    };
+    let (days, rest) = (seconds / 86400, seconds % 86400);
+    let time = format!("{:02}:{:02}:{:02}", rest / 3600, rest / 60 % 60, rest % 60);
+    match days {
+        0 => time,
+        _ => format!("{days}d {time}"),
+    }
+}
+
+pub fn render_text(report: &Report) -> String {
+    let mut out = String::new();
+    let name_width = report
+        .members
+        .iter()
+        .map(|m| m.name.chars().count())
+        .max()
+        .unwrap_or(4)
+        .max(4);
+
+    writeln!(out, "Leaderboard {}", report.event).unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "{:<name_width$}  stars  score  longest streak  current streak",
+        "name"
+    )
+    .unwrap();
+    for member in report.members.iter() {
+        writeln!(
+            out,
+            "{:<name_width$}  {:>5}  {:>5}  {:>14}  {:>14}",
+            member.name,
+            member.stars,
+            member.local_score,
+            member.longest_streak,
+            member.current_streak
+        )
+        .unwrap();
+    }
+
+    for member in report.members.iter().filter(|m| !m.days.is_empty()) {
+        writeln!(out).unwrap();
+        writeln!(out, "{}", member.name).unwrap();
+        writeln!(
+            out,
+            "day  {:>12}  {:>12}  {:>12}",
+            "part 1", "part 2", "delta"
+        )
+        .unwrap();
+        for day in member.days.iter() {
+            writeln!(
+                out,
+                " {:02}  {:>12}  {:>12}  {:>12}",
+                day.day,
+                format_duration(day.part1),
+                format_duration(day.part2),
+                format_duration(day.part2_delta)
+            )
+            .unwrap();
+        }
+    }
+
+    for day in report.days.iter().filter(|d| !d.ranking.is_empty()) {
+        writeln!(out).unwrap();
+        writeln!(out, "Day {}", day.day).unwrap();
+        for entry in day.ranking.iter() {
+            writeln!(
+                out,
+                "{:>3}. {:<name_width$}  {:>12}  {:>12}",
+                entry.rank,
+                entry.name,
+                format_duration(entry.part1),
+                format_duration(entry.part2)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{
+        "event": "2024",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "alice", "stars": 4, "local_score": 8, "global_score": 0,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1733029500, "star_index": 0},
+                            "2": {"get_star_ts": 1733029800, "star_index": 1}},
+                      "2": {"1": {"get_star_ts": 1733116200, "star_index": 2},
+                            "2": {"get_star_ts": 1733119200, "star_index": 3}}}},
+            "2": {"id": 2, "name": null, "stars": 3, "local_score": 5, "global_score": 0,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1733029400, "star_index": 0},
+                            "2": {"get_star_ts": 1733033000, "star_index": 1}},
+                      "2": {"1": {"get_star_ts": 1733200000, "star_index": 2}}}}
+        }
+    }"#;
+
+    #[test]
+    fn test_release_time() {
+        // 2024-12-01T05:00:00Z
+        assert_eq!(release_time(2024, 1), 1733029200);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let report = analyze(&parse(JSON).unwrap(), 2024);
+
+        let alice = &report.members[0];
+        assert_eq!(alice.name, "alice");
+        assert_eq!(alice.days[0].part1, Some(300));
+        assert_eq!(alice.days[0].part2_delta, Some(300));
+        assert_eq!((alice.longest_streak, alice.current_streak), (2, 2));
+
+        let anonymous = &report.members[1];
+        assert_eq!(anonymous.name, "(anonymous user #2)");
+        assert_eq!((anonymous.longest_streak, anonymous.current_streak), (1, 0));
+
+        let ranking: Vec<&str> = report.days[0]
+            .ranking
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(ranking, vec!["alice", "(anonymous user #2)"]);
+        assert_eq!(format_duration(Some(90061)), "1d 01:01:01");
+    }
+}
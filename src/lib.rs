@@ -1 +1,26 @@
+pub mod animation;
+pub mod archive;
+pub mod asciicast;
+pub mod bench;
+pub mod config;
+pub mod counters;
+pub mod diagnostics;
+pub mod direction;
+pub mod explain;
 pub mod file_reader;
+pub mod generate;
+pub mod grid;
+pub mod image;
+pub mod leaderboard;
+pub mod lint;
+pub mod point;
+pub mod progress;
+pub mod region;
+pub mod repl;
+pub mod runner;
+pub mod search;
+pub mod simulation;
+pub mod snapshot;
+pub mod sparse;
+pub mod tomlfile;
+pub mod wrapping;
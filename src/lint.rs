@@ -0,0 +1,488 @@
+//! Validation of puzzle inputs.
+//!
+//! Every day has a [`Validator`] describing the grammar of its input. It
+//! reports each offending line and column with a reason, so a malformed
+//! input is caught before a solution panics somewhere far from the cause.
+
+use std::fmt;
+
+use crate::diagnostics::ParseError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.reason)
+    }
+}
+
+impl Diagnostic {
+    /// The diagnostic with the offending line of `input`, for rendering.
+    pub fn to_parse_error(&self, input: &str) -> ParseError {
+        ParseError::new(input, self.line, self.column, self.reason.clone())
+    }
+}
+
+pub struct Validator {
+    pub day: u8,
+    pub grammar: &'static str,
+    check: fn(&str) -> Vec<Diagnostic>,
+}
+
+impl Validator {
+    pub fn check(&self, input: &str) -> Vec<Diagnostic> {
+        if input.trim().is_empty() {
+            return vec![Diagnostic {
+                line: 1,
+                column: 1,
+                reason: String::from("input is empty"),
+            }];
+        }
+        (self.check)(input)
+    }
+}
+
+pub static VALIDATORS: [Validator; 13] = [
+    Validator {
+        day: 1,
+        grammar: "lines of two numbers separated by spaces, like `3   4`",
+        check: check_day01,
+    },
+    Validator {
+        day: 2,
+        grammar: "lines of numbers separated by spaces, like `7 6 4 2 1`",
+        check: check_day02,
+    },
+    Validator {
+        day: 3,
+        grammar: "any text containing `mul(X,Y)`, `do()` and `don't()` instructions",
+        check: check_day03,
+    },
+    Validator {
+        day: 4,
+        grammar: "a rectangular grid of uppercase letters",
+        check: check_day04,
+    },
+    Validator {
+        day: 5,
+        grammar: "rules like `47|53`, an empty line, then updates like `75,47,61` \
+                  with an odd number of pages",
+        check: check_day05,
+    },
+    Validator {
+        day: 6,
+        grammar: "a rectangular grid of `.` and `#` with exactly one guard `^`",
+        check: check_day06,
+    },
+    Validator {
+        day: 7,
+        grammar: "lines like `190: 10 19`, a test value and at least one number",
+        check: check_day07,
+    },
+    Validator {
+        day: 8,
+        grammar: "a rectangular grid of `.` and antennas (letters and digits)",
+        check: check_day08,
+    },
+    Validator {
+        day: 9,
+        grammar: "a single line of digits",
+        check: check_day09,
+    },
+    Validator {
+        day: 10,
+        grammar: "a rectangular grid of digits",
+        check: check_day10,
+    },
+    Validator {
+        day: 11,
+        grammar: "a single line of numbers separated by spaces",
+        check: check_day11,
+    },
+    Validator {
+        day: 12,
+        grammar: "a rectangular grid of uppercase letters",
+        check: check_day04,
+    },
+    Validator {
+        day: 13,
+        grammar: "blocks of `Button A: X+94, Y+34`, `Button B: X+22, Y+67` and \
+                  `Prize: X=8400, Y=5400`, separated by empty lines",
+        check: check_day13,
+    },
+];
+
+pub fn validator(day: u8) -> Option<&'static Validator> {
+    VALIDATORS.iter().find(|v| v.day == day)
+}
+
+/// Checks the input of a day binary, exiting with the diagnostics when it
+/// does not match the grammar.
+pub fn check_or_exit(day: u8, input: &str) {
+    let Some(validator) = validator(day) else {
+        return;
+    };
+    let diagnostics = validator.check(input);
+    if diagnostics.is_empty() {
+        return;
+    }
+    for diagnostic in diagnostics.iter() {
+        eprintln!("{}\n", diagnostic.to_parse_error(input));
+    }
+    eprintln!("expected {}", validator.grammar);
+    std::process::exit(1);
+}
+
+/// Walks over a single line, producing diagnostics at the current column.
+struct LineParser<'a> {
+    line: &'a str,
+    line_nr: usize,
+    pos: usize,
+}
+
+impl<'a> LineParser<'a> {
+    fn new(line_nr: usize, line: &'a str) -> Self {
+        Self {
+            line,
+            line_nr,
+            pos: 0,
+        }
+    }
+
+    fn error(&self, reason: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            line: self.line_nr,
+            column: self.line[..self.pos].chars().count() + 1,
+            reason: reason.into(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    fn found(&self) -> String {
+        match self.rest().chars().next() {
+            Some(c) => format!("found {c:?}"),
+            None => String::from("found the end of the line"),
+        }
+    }
+
+    fn number(&mut self) -> Result<&'a str, Diagnostic> {
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error(format!("expected a number, {}", self.found())));
+        }
+        let number = &self.rest()[..len];
+        if number.parse::<u64>().is_err() {
+            return Err(self.error("number is too large"));
+        }
+        self.pos += len;
+        Ok(number)
+    }
+
+    fn literal(&mut self, literal: &str) -> Result<(), Diagnostic> {
+        if !self.rest().starts_with(literal) {
+            return Err(self.error(format!("expected {literal:?}, {}", self.found())));
+        }
+        self.pos += literal.len();
+        Ok(())
+    }
+
+    fn spaces(&mut self) -> Result<(), Diagnostic> {
+        let len = self
+            .rest()
+            .find(|c: char| c != ' ')
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error(format!("expected a space, {}", self.found())));
+        }
+        self.pos += len;
+        Ok(())
+    }
+
+    fn at_end(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    fn end(&self) -> Result<(), Diagnostic> {
+        match self.at_end() {
+            true => Ok(()),
+            false => Err(self.error(format!("expected the end of the line, {}", self.found()))),
+        }
+    }
+}
+
+/// Runs `check` on every line, collecting the first problem of each line.
+fn check_lines<'a, I>(
+    lines: I,
+    check: impl Fn(&mut LineParser) -> Result<(), Diagnostic>,
+) -> Vec<Diagnostic>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    lines
+        .filter_map(|(i, line)| check(&mut LineParser::new(i + 1, line)).err())
+        .collect()
+}
+
+fn numbers_separated_by(parser: &mut LineParser, separator: &str) -> Result<usize, Diagnostic> {
+    let mut count = 1;
+    parser.number()?;
+    while !parser.at_end() {
+        match separator {
+            " " => parser.spaces()?,
+            _ => parser.literal(separator)?,
+        }
+        parser.number()?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Reports every character of a grid that isn't `allowed`, and the lines
+/// that are not as wide as the first one.
+fn check_grid(input: &str, allowed: impl Fn(char) -> bool, expected: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let width = input.lines().next().map_or(0, |l| l.chars().count());
+
+    for (i, line) in input.lines().enumerate() {
+        for (j, c) in line.chars().enumerate().filter(|&(_, c)| !allowed(c)) {
+            diagnostics.push(Diagnostic {
+                line: i + 1,
+                column: j + 1,
+                reason: format!("expected {expected}, found {c:?}"),
+            });
+        }
+        let len = line.chars().count();
+        if len != width {
+            diagnostics.push(Diagnostic {
+                line: i + 1,
+                column: len.min(width) + 1,
+                reason: format!("line has {len} cells, but the first line has {width}"),
+            });
+        }
+    }
+    diagnostics
+}
+
+fn check_day01(input: &str) -> Vec<Diagnostic> {
+    check_lines(input.lines().enumerate(), |p| {
+        p.number()?;
+        p.spaces()?;
+        p.number()?;
+        p.end()
+    })
+}
+
+fn check_day02(input: &str) -> Vec<Diagnostic> {
+    check_lines(input.lines().enumerate(), |p| {
+        numbers_separated_by(p, " ")?;
+        p.end()
+    })
+}
+
+fn check_day03(_input: &str) -> Vec<Diagnostic> {
+    // corrupted memory may contain anything
+    vec![]
+}
+
+fn check_day04(input: &str) -> Vec<Diagnostic> {
+    check_grid(input, |c| c.is_ascii_uppercase(), "an uppercase letter")
+}
+
+fn check_day05(input: &str) -> Vec<Diagnostic> {
+    let lines: Vec<(usize, &str)> = input.lines().enumerate().collect();
+    let Some(separator) = lines.iter().position(|(_, l)| l.is_empty()) else {
+        return vec![Diagnostic {
+            line: lines.len() + 1,
+            column: 1,
+            reason: String::from("expected an empty line between the rules and the updates"),
+        }];
+    };
+
+    let mut diagnostics = check_lines(lines[..separator].iter().copied(), |p| {
+        p.number()?;
+        p.literal("|")?;
+        p.number()?;
+        p.end()
+    });
+    diagnostics.extend(check_lines(lines[separator + 1..].iter().copied(), |p| {
+        let n_pages = numbers_separated_by(p, ",")?;
+        if n_pages % 2 == 0 {
+            return Err(Diagnostic {
+                column: 1,
+                ..p.error(format!(
+                    "update has {n_pages} pages, so there is no middle page"
+                ))
+            });
+        }
+        Ok(())
+    }));
+    diagnostics
+}
+
+fn check_day06(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = check_grid(input, |c| matches!(c, '.' | '#' | '^'), "'.', '#' or '^'");
+    let guards: Vec<Diagnostic> = input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '^')
+                .map(move |(j, _)| Diagnostic {
+                    line: i + 1,
+                    column: j + 1,
+                    reason: String::from("there is more than one guard"),
+                })
+        })
+        .collect();
+
+    match guards.len() {
+        0 => diagnostics.push(Diagnostic {
+            line: 1,
+            column: 1,
+            reason: String::from("the map has no guard '^'"),
+        }),
+        1 => {}
+        _ => diagnostics.extend(guards.into_iter().skip(1)),
+    }
+    diagnostics
+}
+
+fn check_day07(input: &str) -> Vec<Diagnostic> {
+    check_lines(input.lines().enumerate(), |p| {
+        p.number()?;
+        p.literal(": ")?;
+        numbers_separated_by(p, " ")?;
+        p.end()
+    })
+}
+
+fn check_day08(input: &str) -> Vec<Diagnostic> {
+    check_grid(
+        input,
+        |c| c == '.' || c.is_ascii_alphanumeric(),
+        "'.' or an antenna",
+    )
+}
+
+fn check_day09(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = check_grid(input, |c| c.is_ascii_digit(), "a digit");
+    diagnostics.extend(single_line(input));
+    diagnostics
+}
+
+fn check_day10(input: &str) -> Vec<Diagnostic> {
+    check_grid(input, |c| c.is_ascii_digit(), "a height digit")
+}
+
+fn check_day11(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = check_lines(input.lines().enumerate().take(1), |p| {
+        numbers_separated_by(p, " ")?;
+        p.end()
+    });
+    diagnostics.extend(single_line(input));
+    diagnostics
+}
+
+fn single_line(input: &str) -> Option<Diagnostic> {
+    input.lines().nth(1).map(|_| Diagnostic {
+        line: 2,
+        column: 1,
+        reason: String::from("expected a single line"),
+    })
+}
+
+fn check_day13(input: &str) -> Vec<Diagnostic> {
+    let lines: Vec<(usize, &str)> = input.lines().enumerate().collect();
+    let mut diagnostics = vec![];
+
+    for block in lines.split(|(_, line)| line.is_empty()) {
+        let Some(&(first, _)) = block.first() else {
+            continue;
+        };
+        if block.len() != 3 {
+            diagnostics.push(Diagnostic {
+                line: first + 1,
+                column: 1,
+                reason: format!("machine has {} lines instead of 3", block.len()),
+            });
+        }
+
+        let expected = [
+            ("Button A: X+", ", Y+"),
+            ("Button B: X+", ", Y+"),
+            ("Prize: X=", ", Y="),
+        ];
+        for (&(i, line), (x, y)) in block.iter().zip(expected) {
+            let mut parser = LineParser::new(i + 1, line);
+            diagnostics.extend(coordinates(&mut parser, x, y).err());
+        }
+    }
+    diagnostics
+}
+
+fn coordinates(parser: &mut LineParser, x: &str, y: &str) -> Result<(), Diagnostic> {
+    parser.literal(x)?;
+    parser.number()?;
+    parser.literal(y)?;
+    parser.number()?;
+    parser.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day13_diagnostics() {
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y67\nPrize: X=8400, Y=5400\n\n\
+                     Button A: X+1, Y+2\nPrize: X=8400, Y=5400\n";
+        let diagnostics = validator(13).unwrap().check(input);
+        let positions: Vec<(usize, usize)> =
+            diagnostics.iter().map(|d| (d.line, d.column)).collect();
+        assert_eq!(positions, vec![(2, 15), (5, 1), (6, 1)]);
+        assert_eq!(diagnostics[0].reason, "expected \", Y+\", found ','");
+    }
+
+    #[test]
+    fn test_day05_diagnostics() {
+        let input = "47|53\n97-13\n\n75,47,61\n75,47\n";
+        let diagnostics = validator(5).unwrap().check(input);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic {
+                    line: 2,
+                    column: 3,
+                    reason: String::from("expected \"|\", found '-'"),
+                },
+                Diagnostic {
+                    line: 5,
+                    column: 1,
+                    reason: String::from("update has 2 pages, so there is no middle page"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grid_diagnostics() {
+        let diagnostics = validator(6).unwrap().check("..#\n.x\n.?!\n");
+        let positions: Vec<(usize, usize)> =
+            diagnostics.iter().map(|d| (d.line, d.column)).collect();
+        assert_eq!(positions, vec![(2, 2), (2, 3), (3, 2), (3, 3), (1, 1)]);
+    }
+}
@@ -0,0 +1,240 @@
+//! Tracking of the solved puzzle parts.
+//!
+//! The day binaries [`record`] their answers in the progress file, through
+//! [`runner::answer`]. An answer counts as a star once it matches the answers
+//! file, which holds the answers confirmed on the website (`aoc verify`
+//! copies an answer over).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::runner;
+use crate::tomlfile::{self, TomlError};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Progress {
+    days: BTreeMap<String, BTreeMap<String, PartProgress>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartProgress {
+    pub answer: String,
+    pub verified: bool,
+    pub first_solved: Option<String>,
+    /// Name of the binary that produced the answer
+    pub implementation: String,
+}
+
+/// The answers confirmed on the website, by day and part.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+pub fn day_key(day: u8) -> String {
+    format!("day{day:02}")
+}
+
+fn part_key(part: u8) -> String {
+    format!("part{part}")
+}
+
+impl Progress {
+    pub fn load(path: &Path) -> Result<Self, TomlError> {
+        tomlfile::load(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TomlError> {
+        tomlfile::save(self, path)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&PartProgress> {
+        self.days.get(&day_key(day))?.get(&part_key(part))
+    }
+
+    pub fn stars(&self) -> usize {
+        self.days
+            .values()
+            .flat_map(|parts| parts.values())
+            .filter(|part| part.verified)
+            .count()
+    }
+
+    /// Stores an answer produced by `implementation`. A verified answer is
+    /// never replaced, so the first solve keeps its date and implementation.
+    pub fn update(
+        &mut self,
+        day: u8,
+        part: u8,
+        answer: &str,
+        implementation: &str,
+        answers: &Answers,
+    ) -> &PartProgress {
+        let verified = answers.get(day, part) == Some(answer);
+        let entry = self
+            .days
+            .entry(day_key(day))
+            .or_default()
+            .entry(part_key(part))
+            .or_insert_with(|| PartProgress {
+                answer: answer.to_string(),
+                verified: false,
+                first_solved: None,
+                implementation: implementation.to_string(),
+            });
+
+        if !entry.verified {
+            entry.answer = answer.to_string();
+            entry.implementation = implementation.to_string();
+            if verified {
+                entry.verified = true;
+                entry.first_solved = Some(timestamp(SystemTime::now()));
+            }
+        }
+        entry
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, TomlError> {
+        tomlfile::load(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TomlError> {
+        tomlfile::save(self, path)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.days
+            .get(&day_key(day))?
+            .get(&part_key(part))
+            .map(String::as_str)
+    }
+
+    pub fn set(&mut self, day: u8, part: u8, answer: &str) {
+        self.days
+            .entry(day_key(day))
+            .or_default()
+            .insert(part_key(part), answer.to_string());
+    }
+}
+
+/// Records the answer to a part in the progress file.
+///
+/// Runs on another input or with changed puzzle settings are not recorded,
+/// see [`Config::solves_puzzle`]. Problems with the files are reported, but
+/// never stop the day. Under `aoc all` the runner records the answers the
+/// days [`runner::report`] instead.
+pub fn record(config: &Config, day: u8, part: u8, answer: impl Display) {
+    if std::env::var_os(runner::RUNNER_ENV).is_some() || !config.solves_puzzle() {
+        return;
+    }
+    if let Err(e) = record_as(
+        config,
+        day,
+        part,
+        &answer.to_string(),
+        &implementation_name(),
+    ) {
+        eprintln!("warning: could not record the answer: {e}");
+    }
+}
+
+/// Records an answer produced by the binary `implementation`.
+pub fn record_as(
+    config: &Config,
+    day: u8,
+    part: u8,
+    answer: &str,
+    implementation: &str,
+) -> Result<(), TomlError> {
+    let answers = Answers::load(&config.answers_path())?;
+    let mut progress = Progress::load(&config.progress_path())?;
+
+    if let Some(known) = answers.get(day, part).filter(|&known| known != answer) {
+        eprintln!(
+            "warning: {} part {part} gave {answer}, but the verified answer is {known}",
+            day_key(day)
+        );
+    }
+
+    progress.update(day, part, answer, implementation, &answers);
+    progress.save(&config.progress_path())
+}
+
+fn implementation_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| String::from("unknown"))
+}
+
+/// Formats a time as an ISO 8601 UTC timestamp, like `2024-12-01T05:12:44Z`.
+pub fn timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let seconds_of_day = seconds % 86400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date,
+/// following Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_secs(1733029964);
+        assert_eq!(timestamp(time), "2024-12-01T05:12:44Z");
+        assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_update_keeps_first_solve() {
+        let mut answers = Answers::default();
+        let mut progress = Progress::default();
+
+        let entry = progress.update(1, 1, "11", "day01", &answers);
+        assert!(!entry.verified);
+
+        answers.set(1, 1, "11");
+        let entry = progress.update(1, 1, "11", "day01", &answers).clone();
+        assert!(entry.verified);
+        assert!(entry.first_solved.is_some());
+
+        let later = progress.update(1, 1, "12", "day01_faster", &answers);
+        assert_eq!(later, &entry);
+        assert_eq!(progress.stars(), 1);
+    }
+}
@@ -0,0 +1,212 @@
+//! Connected regions of a grid.
+//!
+//! [`label`] splits a grid into regions of cells connected through their
+//! sides, or through their corners as well, where `same(a, b)` says whether
+//! two neighboring cells belong together. Every region comes with the
+//! measurements the puzzles ask about, like the length of the fence around
+//! it and the number of straight sides of that fence.
+
+use crate::direction::Direction;
+use crate::grid::Grid;
+use crate::search;
+
+/// Which cells count as neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// The cells sharing a side
+    Four,
+    /// The cells sharing a side or a corner
+    Eight,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Index of the region in [`Regions::regions`]
+    pub label: usize,
+    /// The positions of the cells, row by row
+    pub cells: Vec<(usize, usize)>,
+    /// The number of cell sides between the region and the rest of the grid,
+    /// the edge of the grid included
+    pub perimeter: usize,
+    /// The number of straight sections of the perimeter
+    pub sides: usize,
+    /// The top left and bottom right corners of the region, inclusive
+    pub bounds: ((usize, usize), (usize, usize)),
+    /// The label of the region around this one, when every neighbor of its
+    /// cells belongs to that region
+    pub enclosed_by: Option<usize>,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    /// The label of the region of every cell
+    pub labels: Grid<usize>,
+    /// The regions, by the first of their cells in reading order
+    pub regions: Vec<Region>,
+}
+
+/// Splits `grid` into the connected regions of cells for which `same` holds
+/// between neighbors.
+pub fn label<T, F>(grid: &Grid<T>, connectivity: Connectivity, mut same: F) -> Regions
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let mut labels: Grid<Option<usize>> = Grid::filled(grid.rows(), grid.cols(), None);
+    let mut regions: Vec<Vec<(usize, usize)>> = vec![];
+
+    for (start, _) in grid.iter() {
+        if labels[start].is_some() {
+            continue;
+        }
+        let mut cells: Vec<(usize, usize)> = search::reachable([start], |&cell| {
+            neighbors(grid, connectivity, cell)
+                .filter(|&next| same(&grid[cell], &grid[next]))
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .collect();
+        cells.sort();
+        for &cell in &cells {
+            labels[cell] = Some(regions.len());
+        }
+        regions.push(cells);
+    }
+
+    let labels = labels.map(|label| label.expect("every cell is in a region"));
+    let regions = regions
+        .into_iter()
+        .enumerate()
+        .map(|(label, cells)| measure(&labels, connectivity, label, cells))
+        .collect();
+    Regions { labels, regions }
+}
+
+fn neighbors<T>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    cell: (usize, usize),
+) -> Box<dyn Iterator<Item = (usize, usize)> + '_> {
+    match connectivity {
+        Connectivity::Four => Box::new(grid.neighbors4(cell)),
+        Connectivity::Eight => Box::new(grid.neighbors8(cell)),
+    }
+}
+
+fn measure(
+    labels: &Grid<usize>,
+    connectivity: Connectivity,
+    label: usize,
+    cells: Vec<(usize, usize)>,
+) -> Region {
+    let inside = |cell: (usize, usize), direction: Direction| {
+        labels.step(cell, direction).map(|next| labels[next]) == Some(label)
+    };
+
+    let mut perimeter = 0;
+    let mut sides = 0;
+    for &cell in &cells {
+        for direction in Direction::CARDINAL {
+            if !inside(cell, direction) {
+                perimeter += 1;
+            }
+            // a side ends at every corner of the region, one of the cell
+            // sticking out or one of the region going in around it
+            let next = direction.turn_right();
+            match (inside(cell, direction), inside(cell, next)) {
+                (false, false) => sides += 1,
+                (true, true) if !inside(cell, direction.rotate(1)) => sides += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let rows = cells.iter().map(|&(row, _)| row);
+    let cols = cells.iter().map(|&(_, col)| col);
+    let bounds = (
+        (rows.clone().min().unwrap(), cols.clone().min().unwrap()),
+        (rows.max().unwrap(), cols.max().unwrap()),
+    );
+
+    // every neighbor has to be on the grid and in the same other region
+    let directions: &[Direction] = match connectivity {
+        Connectivity::Four => &Direction::CARDINAL,
+        Connectivity::Eight => &Direction::ALL,
+    };
+    let mut around = None;
+    let mut enclosed = true;
+    for &cell in &cells {
+        for &direction in directions {
+            match labels.step(cell, direction).map(|next| labels[next]) {
+                None => enclosed = false,
+                Some(other) if other == label => {}
+                Some(other) => match around {
+                    None => around = Some(other),
+                    Some(around) if around != other => enclosed = false,
+                    Some(_) => {}
+                },
+            }
+        }
+    }
+
+    Region {
+        label,
+        cells,
+        perimeter,
+        sides,
+        bounds,
+        enclosed_by: around.filter(|_| enclosed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn garden(input: &str) -> Regions {
+        let grid = Grid::parse(input, Some).unwrap();
+        label(&grid, Connectivity::Four, |a, b| a == b)
+    }
+
+    #[test]
+    fn test_garden() {
+        let regions = garden("AAAA\nBBCD\nBBCC\nEEEC\n");
+        let measured: Vec<(usize, usize, usize)> = regions
+            .regions
+            .iter()
+            .map(|region| (region.area(), region.perimeter, region.sides))
+            .collect();
+        assert_eq!(
+            measured,
+            [(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+        assert_eq!(regions.labels[(2, 3)], 2);
+        assert_eq!(regions.regions[2].bounds, ((1, 2), (3, 3)));
+        assert_eq!(regions.regions[2].cells, [(1, 2), (2, 2), (2, 3), (3, 3)]);
+        assert_eq!(regions.regions[3].enclosed_by, None);
+    }
+
+    #[test]
+    fn test_enclosed() {
+        let regions = garden("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO\n");
+        assert_eq!(regions.regions.len(), 5);
+        let outer = &regions.regions[0];
+        assert_eq!((outer.area(), outer.perimeter, outer.sides), (21, 36, 20));
+        assert!(regions.regions[1..]
+            .iter()
+            .all(|region| region.enclosed_by == Some(0)));
+        assert_eq!(outer.enclosed_by, None);
+
+        // cells touching at a corner are one region, even across another one
+        let grid = Grid::parse("XO\nOX\n", Some).unwrap();
+        let regions = label(&grid, Connectivity::Eight, |a, b| a == b);
+        assert_eq!(regions.regions.len(), 2);
+        assert_eq!(regions.regions[0].cells, [(0, 0), (1, 1)]);
+        assert_eq!(regions.regions[0].sides, 8);
+    }
+}
@@ -0,0 +1,150 @@
+//! A small read-eval-print loop for poking at the parsed state of a day.
+//!
+//! A day implements [`Repl`] for its puzzle structure and starts the loop
+//! with [`run`] when the binary is started with `--repl`. The loop itself
+//! handles `help` and `quit`, everything else is passed on to the day.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+pub trait Repl {
+    /// Executes a single command, returning the text to print.
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, ReplError>;
+
+    /// One line per supported command, listed by `help`.
+    fn help(&self) -> &[&str];
+
+    fn prompt(&self) -> String {
+        String::from("> ")
+    }
+}
+
+/// Runs the loop on stdin and stdout until `quit` or end of input.
+pub fn run<R: Repl>(state: &mut R) {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    run_with(state, stdin.lock(), stdout.lock()).expect("Unable to use the terminal");
+}
+
+pub fn run_with<R, I, O>(state: &mut R, input: I, mut output: O) -> io::Result<()>
+where
+    R: Repl,
+    I: BufRead,
+    O: Write,
+{
+    let mut lines = input.lines();
+    loop {
+        write!(output, "{}", state.prompt())?;
+        output.flush()?;
+
+        let Some(line) = lines.next() else {
+            writeln!(output)?;
+            return Ok(());
+        };
+        let line = line?;
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            continue;
+        };
+        let args: Vec<&str> = words.collect();
+
+        match name {
+            "quit" | "exit" | "q" => return Ok(()),
+            "help" | "?" => {
+                for line in state.help() {
+                    writeln!(output, "  {line}")?;
+                }
+                writeln!(output, "  help")?;
+                writeln!(output, "  quit")?;
+            }
+            _ => match state.command(name, &args) {
+                Ok(text) if text.is_empty() => {}
+                Ok(text) => writeln!(output, "{}", text.trim_end())?,
+                Err(e) => writeln!(output, "error: {e}")?,
+            },
+        }
+    }
+}
+
+/// Parses the `row col` arguments of commands like `at 3 4`.
+pub fn parse_position(args: &[&str]) -> Result<(usize, usize), ReplError> {
+    match args {
+        [row, col] => Ok((parse_arg(row)?, parse_arg(col)?)),
+        _ => Err(ReplError::Usage("expected a row and a column")),
+    }
+}
+
+/// Parses a comma separated list like `75,47,61`.
+pub fn parse_list<T: std::str::FromStr>(arg: &str) -> Result<Vec<T>, ReplError> {
+    arg.split(',').map(|x| parse_arg(x.trim())).collect()
+}
+
+pub fn parse_arg<T: std::str::FromStr>(arg: &str) -> Result<T, ReplError> {
+    arg.parse()
+        .map_err(|_| ReplError::InvalidArgument(arg.to_string()))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplError {
+    UnknownCommand(String),
+    InvalidArgument(String),
+    Usage(&'static str),
+}
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplError::UnknownCommand(name) => {
+                write!(f, "unknown command {name}, type help for a list")
+            }
+            ReplError::InvalidArgument(arg) => write!(f, "invalid argument {arg}"),
+            ReplError::Usage(usage) => write!(f, "{usage}"),
+        }
+    }
+}
+
+impl Error for ReplError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter {
+        value: i32,
+    }
+
+    impl Repl for Counter {
+        fn command(&mut self, name: &str, args: &[&str]) -> Result<String, ReplError> {
+            match name {
+                "add" => {
+                    for x in parse_list::<i32>(args.first().copied().unwrap_or(""))? {
+                        self.value += x;
+                    }
+                    Ok(String::new())
+                }
+                "show" => Ok(self.value.to_string()),
+                _ => Err(ReplError::UnknownCommand(name.to_string())),
+            }
+        }
+
+        fn help(&self) -> &[&str] {
+            &["add 1,2,3", "show"]
+        }
+    }
+
+    #[test]
+    fn test_run_commands() {
+        let mut counter = Counter { value: 0 };
+        let input = "add 1,2\n\nshow\nadd x\nfoo\nquit\nshow\n";
+        let mut output = vec![];
+        run_with(&mut counter, input.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "> > > 3\n> error: invalid argument x\n> error: unknown command foo, type help for a list\n> "
+        );
+        assert_eq!(counter.value, 3);
+    }
+}
@@ -0,0 +1,393 @@
+//! Running the day binaries side by side.
+//!
+//! `aoc all` starts the compiled day binaries on a pool of threads. The days
+//! run with [`RUNNER_ENV`] set, which makes [`report`] print the answers as
+//! [`ANSWER_PREFIX`] lines, so the runner can collect them and record them
+//! one at a time. The [`crate::counters`] of each part come along as
+//! [`COUNTER_PREFIX`] lines.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Write as _;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::counters;
+use crate::progress;
+
+pub const RUNNER_ENV: &str = "AOC_RUNNER";
+pub const ANSWER_PREFIX: &str = "aoc-answer: ";
+pub const COUNTER_PREFIX: &str = "aoc-counter: ";
+
+/// A day binary to run.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug)]
+pub struct RunResult {
+    pub day: u8,
+    pub name: String,
+    /// Answers by part
+    pub answers: BTreeMap<u8, String>,
+    /// Counters by part and name
+    pub counters: BTreeMap<u8, BTreeMap<String, u64>>,
+    pub wall_time: Duration,
+    /// CPU time of the process, where the platform reports it
+    pub cpu_time: Option<Duration>,
+    pub status: RunStatus,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RunStatus {
+    Ok,
+    Failed(String),
+    TimedOut,
+}
+
+/// Runs every job with the same arguments, at most `n_threads` at a time.
+/// The results are ordered by day and name.
+pub fn run_all(
+    jobs: &[Job],
+    args: &[String],
+    n_threads: usize,
+    timeout: Duration,
+) -> Vec<RunResult> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(vec![]);
+
+    thread::scope(|scope| {
+        for _ in 0..n_threads.max(1) {
+            scope.spawn(|| {
+                while let Some(job) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = run(job, args, timeout);
+                    results.lock().unwrap().push(result);
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    results
+}
+
+fn run(job: &Job, args: &[String], timeout: Duration) -> RunResult {
+    let start = Instant::now();
+    let mut result = RunResult {
+        day: job.day,
+        name: job.name.clone(),
+        answers: BTreeMap::new(),
+        counters: BTreeMap::new(),
+        wall_time: Duration::ZERO,
+        cpu_time: None,
+        status: RunStatus::Ok,
+    };
+
+    let child = Command::new(&job.path)
+        .args(args)
+        .env(RUNNER_ENV, "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            result.status = RunStatus::Failed(format!("could not start: {e}"));
+            return result;
+        }
+    };
+
+    // read the pipes on their own threads, so a full pipe never blocks the day
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let waited = wait_with_timeout(&mut child, timeout);
+    result.wall_time = start.elapsed();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    for line in stdout.lines() {
+        if let Some((day, part, answer)) = parse_answer(line) {
+            if day == job.day {
+                result.answers.insert(part, answer.to_string());
+            }
+        }
+        if let Some((day, part, name, value)) = parse_counter(line) {
+            if day == job.day {
+                let counters = result.counters.entry(part).or_default();
+                counters.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    result.status = match waited {
+        Ok(Some((status, cpu_time))) => {
+            result.cpu_time = cpu_time;
+            match status.success() {
+                true => RunStatus::Ok,
+                false => RunStatus::Failed(format!("{status}: {}", failure_reason(&stderr))),
+            }
+        }
+        Ok(None) => RunStatus::TimedOut,
+        Err(e) => RunStatus::Failed(format!("could not wait for the process: {e}")),
+    };
+    result
+}
+
+/// The panic message, or else the first line the day printed to stderr.
+fn failure_reason(stderr: &str) -> &str {
+    let mut lines = stderr.lines().map(str::trim).filter(|l| !l.is_empty());
+    let first = lines.clone().next().unwrap_or("no output");
+    match lines.position(|line| line.contains("panicked at")) {
+        Some(_) => lines.next().unwrap_or(first),
+        None => first,
+    }
+}
+
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut text);
+        }
+        text
+    })
+}
+
+/// Waits for the child to exit, killing it after `timeout`. Returns `None`
+/// when the child was killed.
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Duration,
+) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    let start = Instant::now();
+    loop {
+        if let Some(exited) = try_wait(child)? {
+            return Ok(Some(exited));
+        }
+        if start.elapsed() > timeout {
+            child.kill()?;
+            while try_wait(child)?.is_none() {
+                thread::sleep(Duration::from_millis(5));
+            }
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Reaps the child if it exited, together with the CPU time it used.
+#[cfg(unix)]
+fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    // SAFETY: rusage is plain old data, all zeroes is a valid value
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: the pid belongs to our own child, which is reaped only here
+    let pid = unsafe { libc::wait4(child.id() as i32, &mut status, libc::WNOHANG, &mut usage) };
+    match pid {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(None),
+        _ => {
+            let cpu_time = timeval(usage.ru_utime) + timeval(usage.ru_stime);
+            Ok(Some((ExitStatus::from_raw(status), Some(cpu_time))))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn timeval(time: libc::timeval) -> Duration {
+    Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000)
+}
+
+#[cfg(not(unix))]
+fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    Ok(child.try_wait()?.map(|status| (status, None)))
+}
+
+/// Reports the answer to a part of a day and records it in the progress
+/// file, see [`report`] and [`progress::record`].
+pub fn answer(config: &Config, day: u8, part: u8, answer: impl Display) {
+    report(day, part, &answer);
+    progress::record(config, day, part, answer);
+}
+
+/// Prints the [`counters`] of a part, taking them. Under the runner the
+/// answer and the counters are printed as the lines it collects.
+pub fn report(day: u8, part: u8, answer: impl Display) {
+    let counters = counters::take();
+    if std::env::var_os(RUNNER_ENV).is_some() {
+        println!("{ANSWER_PREFIX}{day} {part} {answer}");
+        for (name, value) in counters.iter() {
+            println!("{COUNTER_PREFIX}{day} {part} {value} {name}");
+        }
+    } else if !counters.is_empty() {
+        println!("counters of part {part}: {}", counters::render(&counters));
+    }
+}
+
+/// Parses an answer line printed by a day: `aoc-answer: <day> <part> <answer>`.
+pub fn parse_answer(line: &str) -> Option<(u8, u8, &str)> {
+    let rest = line.strip_prefix(ANSWER_PREFIX)?;
+    let mut parts = rest.splitn(3, ' ');
+    let day = parts.next()?.parse().ok()?;
+    let part = parts.next()?.parse().ok()?;
+    Some((day, part, parts.next()?))
+}
+
+/// Parses a counter line printed by a day:
+/// `aoc-counter: <day> <part> <value> <name>`.
+pub fn parse_counter(line: &str) -> Option<(u8, u8, &str, u64)> {
+    let rest = line.strip_prefix(COUNTER_PREFIX)?;
+    let mut parts = rest.splitn(4, ' ');
+    let day = parts.next()?.parse().ok()?;
+    let part = parts.next()?.parse().ok()?;
+    let value = parts.next()?.parse().ok()?;
+    Some((day, part, parts.next()?, value))
+}
+
+fn format_time(time: Duration) -> String {
+    format!("{:.3}s", time.as_secs_f64())
+}
+
+pub fn render_report(results: &[RunResult], wall_time: Duration) -> String {
+    let mut out = String::new();
+    let name_width = results
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(14)
+        .max(14);
+    let answer_width = results
+        .iter()
+        .flat_map(|r| r.answers.values())
+        .map(|a| a.len())
+        .max()
+        .unwrap_or(6)
+        .max(6);
+
+    writeln!(
+        out,
+        "day  {:<name_width$}  {:>answer_width$}  {:>answer_width$}  {:>9}  {:>9}  status",
+        "implementation", "part 1", "part 2", "wall", "cpu"
+    )
+    .unwrap();
+    for result in results.iter() {
+        let answer = |part| result.answers.get(&part).map_or("-", String::as_str);
+        let status = match &result.status {
+            RunStatus::Ok => String::from("ok"),
+            RunStatus::Failed(reason) => format!("failed, {reason}"),
+            RunStatus::TimedOut => String::from("timed out"),
+        };
+        writeln!(
+            out,
+            " {:02}  {:<name_width$}  {:>answer_width$}  {:>answer_width$}  {:>9}  {:>9}  {status}",
+            result.day,
+            result.name,
+            answer(1),
+            answer(2),
+            format_time(result.wall_time),
+            result.cpu_time.map_or(String::from("-"), format_time),
+        )
+        .unwrap();
+    }
+
+    if results.iter().any(|r| !r.counters.is_empty()) {
+        writeln!(out).unwrap();
+        writeln!(out, "counters:").unwrap();
+        for result in results.iter() {
+            for (part, counters) in result.counters.iter() {
+                for (name, value) in counters.iter() {
+                    writeln!(
+                        out,
+                        " {:02}  {:<name_width$}  part {part}  {name}: {value}",
+                        result.day, result.name
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+
+    let cpu_time: Duration = results.iter().filter_map(|r| r.cpu_time).sum();
+    let failures = results.iter().filter(|r| r.status != RunStatus::Ok).count();
+    writeln!(out).unwrap();
+    writeln!(out, "ran {} binaries, {failures} failed", results.len()).unwrap();
+    writeln!(out, "total wall time: {}", format_time(wall_time)).unwrap();
+    writeln!(out, "sum of cpu time: {}", format_time(cpu_time)).unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer() {
+        assert_eq!(
+            parse_answer("aoc-answer: 7 2 37598910447546"),
+            Some((7, 2, "37598910447546"))
+        );
+        assert_eq!(parse_answer("The answer to part a is 3"), None);
+        assert_eq!(
+            parse_counter("aoc-counter: 5 2 1234 rule checks"),
+            Some((5, 2, "rule checks", 1234))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_all() {
+        let jobs = vec![
+            Job {
+                day: 2,
+                name: String::from("sh"),
+                path: PathBuf::from("/bin/sh"),
+            },
+            Job {
+                day: 1,
+                name: String::from("sh"),
+                path: PathBuf::from("/bin/sh"),
+            },
+        ];
+        let script = String::from(
+            "echo 'aoc-answer: 1 1 42'; echo 'aoc-answer: 2 2 7'; echo 'aoc-counter: 1 1 3 bfs pops'",
+        );
+        let results = run_all(
+            &jobs,
+            &[String::from("-c"), script],
+            2,
+            Duration::from_secs(5),
+        );
+
+        assert_eq!(results[0].day, 1);
+        assert_eq!(
+            results[0].answers,
+            BTreeMap::from([(1, String::from("42"))])
+        );
+        assert_eq!(results[1].answers, BTreeMap::from([(2, String::from("7"))]));
+        assert_eq!(results[0].counters[&1]["bfs pops"], 3);
+        assert!(render_report(&results, Duration::ZERO).contains("part 1  bfs pops: 3"));
+        assert!(results.iter().all(|r| r.status == RunStatus::Ok));
+
+        let sleeping = run_all(
+            &jobs[..1],
+            &[String::from("-c"), String::from("exec sleep 5")],
+            1,
+            Duration::from_millis(50),
+        );
+        assert_eq!(sleeping[0].status, RunStatus::TimedOut);
+    }
+}
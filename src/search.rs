@@ -0,0 +1,424 @@
+//! Searches of graphs given by a successor function.
+//!
+//! The graph is never built: the searches call `successors(&node)` for the
+//! neighbors of a node, or for the neighbors with the cost of the step to
+//! them in the weighted searches. Nodes can be anything hashable, like grid
+//! positions or a position with a direction. The searches that look for a
+//! goal take a predicate and stop at the first node matching it, returning
+//! the path from the start to that node.
+//!
+//! When there is more than one shortest path, [`dijkstra_all`] and
+//! [`bfs_all`] keep all of them as a [`ShortestPaths`] graph, which counts
+//! the paths to a node and finds the nodes on any of them.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// The number of steps to every node reachable from `starts`.
+pub fn bfs_distances<N, F, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: F,
+) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !distances.contains_key(&start) {
+            distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node];
+        for next in successors(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// A path with the fewest steps from `start` to a node matching `goal`.
+pub fn bfs<N, F, I, G>(start: N, mut successors: F, mut goal: G) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut parents = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if goal(&node) {
+            return Some(rebuild_path(&parents, node));
+        }
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// A path from `start` to a node matching `goal`, depth first, so not
+/// necessarily the shortest.
+pub fn dfs<N, F, I, G>(start: N, mut successors: F, mut goal: G) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut parents = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        if goal(&node) {
+            return Some(rebuild_path(&parents, node));
+        }
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                stack.push(next);
+            }
+        }
+    }
+    None
+}
+
+/// Every node reachable from `starts`, the starts included.
+pub fn reachable<N, F, I>(starts: impl IntoIterator<Item = N>, mut successors: F) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut stack = vec![];
+    for start in starts {
+        if seen.insert(start.clone()) {
+            stack.push(start);
+        }
+    }
+    while let Some(node) = stack.pop() {
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
+
+/// The cost of the cheapest path to every node reachable from `start`.
+pub fn dijkstra_distances<N, F, I>(start: N, successors: F) -> HashMap<N, u64>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let (explored, _) = explore(start, successors, |_| 0, |_| false);
+    explored.nodes.into_iter().zip(explored.cost).collect()
+}
+
+/// Every cheapest path from `start` to the nodes reachable from it. The
+/// steps have to cost more than nothing.
+pub fn dijkstra_all<N, F, I>(start: N, successors: F) -> ShortestPaths<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let (explored, _) = explore(start, successors, |_| 0, |_| false);
+    let predecessors = explored
+        .parents
+        .iter()
+        .map(|parents| parents.iter().map(|&i| explored.nodes[i].clone()).collect())
+        .collect::<Vec<_>>();
+    ShortestPaths {
+        cost: explored.nodes.iter().cloned().zip(explored.cost).collect(),
+        predecessors: explored.nodes.into_iter().zip(predecessors).collect(),
+    }
+}
+
+/// Every path with the fewest steps from `start` to the nodes reachable from
+/// it.
+pub fn bfs_all<N, F, I>(start: N, mut successors: F) -> ShortestPaths<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    dijkstra_all(start, |node: &N| {
+        successors(node).into_iter().map(|next| (next, 1))
+    })
+}
+
+/// The cheapest path from `start` to a node matching `goal`, with its cost.
+pub fn dijkstra<N, F, I, G>(start: N, successors: F, goal: G) -> Option<(Vec<N>, u64)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| 0, goal)
+}
+
+/// The cheapest path from `start` to a node matching `goal`, with its cost.
+/// `heuristic` estimates the cost from a node to the goal, which it must
+/// never overestimate for the path to be the cheapest.
+pub fn astar<N, F, I, H, G>(start: N, successors: F, heuristic: H, goal: G) -> Option<(Vec<N>, u64)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let (explored, found) = explore(start, successors, heuristic, goal);
+    let found = found?;
+    let mut path = vec![];
+    let mut current = Some(found);
+    while let Some(i) = current {
+        path.push(explored.nodes[i].clone());
+        current = explored.parents[i].first().copied();
+    }
+    path.reverse();
+    Some((path, explored.cost[found]))
+}
+
+/// The shortest paths from a start, as the graph of the steps on them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPaths<N: Eq + Hash> {
+    /// The cost of the cheapest path to every reachable node
+    pub cost: HashMap<N, u64>,
+    /// The nodes right before every node on its cheapest paths, none for
+    /// the start
+    pub predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash> ShortestPaths<N> {
+    /// Every node on a cheapest path to `end`, the start and `end` included.
+    /// Empty when `end` can't be reached.
+    pub fn nodes_on_paths(&self, end: &N) -> HashSet<N> {
+        let mut nodes = HashSet::new();
+        if !self.cost.contains_key(end) {
+            return nodes;
+        }
+        let mut stack = vec![end.clone()];
+        nodes.insert(end.clone());
+        while let Some(node) = stack.pop() {
+            for predecessor in &self.predecessors[&node] {
+                if nodes.insert(predecessor.clone()) {
+                    stack.push(predecessor.clone());
+                }
+            }
+        }
+        nodes
+    }
+
+    /// The number of cheapest paths to `end`, or `None` when it doesn't fit
+    /// in a `u64`.
+    pub fn count_paths(&self, end: &N) -> Option<u64> {
+        // the predecessors of a node are cheaper, so they are counted first
+        let mut nodes: Vec<N> = self.nodes_on_paths(end).into_iter().collect();
+        nodes.sort_by_key(|node| self.cost[node]);
+
+        let mut counts: HashMap<&N, Option<u64>> = HashMap::new();
+        for node in &nodes {
+            let predecessors = &self.predecessors[node];
+            let count = match predecessors.is_empty() {
+                true => Some(1),
+                false => predecessors.iter().try_fold(0u64, |sum, predecessor| {
+                    sum.checked_add(counts[predecessor]?)
+                }),
+            };
+            counts.insert(node, count);
+        }
+        counts.get(end).copied().unwrap_or(Some(0))
+    }
+}
+
+/// The nodes seen by a weighted search, by the order they were found in.
+struct Explored<N> {
+    nodes: Vec<N>,
+    cost: Vec<u64>,
+    /// The nodes right before every node on its cheapest paths
+    parents: Vec<Vec<usize>>,
+}
+
+/// Best first search from `start`, returning the index of the node matching
+/// `goal` if it finds one.
+fn explore<N, F, I, H, G>(
+    start: N,
+    mut successors: F,
+    mut heuristic: H,
+    mut goal: G,
+) -> (Explored<N>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut explored = Explored {
+        nodes: vec![start],
+        cost: vec![0],
+        parents: vec![vec![]],
+    };
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > explored.cost[i] {
+            // a cheaper path to the node was found after this one was queued
+            continue;
+        }
+        let node = explored.nodes[i].clone();
+        if goal(&node) {
+            return (explored, Some(i));
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            let j = match index.get(&next) {
+                Some(&j) if next_cost == explored.cost[j] => {
+                    // another path as cheap as the best one
+                    if !explored.parents[j].contains(&i) {
+                        explored.parents[j].push(i);
+                    }
+                    continue;
+                }
+                Some(&j) if next_cost > explored.cost[j] => continue,
+                Some(&j) => j,
+                None => {
+                    index.insert(next.clone(), explored.nodes.len());
+                    explored.nodes.push(next);
+                    explored.cost.push(next_cost);
+                    explored.parents.push(vec![]);
+                    explored.nodes.len() - 1
+                }
+            };
+            explored.cost[j] = next_cost;
+            explored.parents[j] = vec![i];
+            heap.push(Reverse((
+                next_cost + heuristic(&explored.nodes[j]),
+                next_cost,
+                j,
+            )));
+        }
+    }
+    (explored, None)
+}
+
+/// The path from the start to `end`, following the parents back.
+fn rebuild_path<N: Clone + Eq + Hash>(parents: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut path = vec![end];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "S..#....\n.#.#.##.\n.#...#..\n.####.#.\n.....##E\n";
+
+    fn maze() -> Grid<char> {
+        Grid::parse(MAZE, Some).unwrap()
+    }
+
+    fn open(grid: &Grid<char>, position: (usize, usize)) -> Vec<(usize, usize)> {
+        grid.neighbors4(position)
+            .filter(|&next| grid[next] != '#')
+            .collect()
+    }
+
+    #[test]
+    fn test_unweighted() {
+        let grid = maze();
+        let end = (4, 7);
+
+        let distances = bfs_distances([(0, 0)], |&p| open(&grid, p));
+        assert_eq!(distances[&end], 15);
+        assert_eq!(distances.len(), 25);
+        assert!(!distances.contains_key(&(3, 5)));
+
+        let path = bfs((0, 0), |&p| open(&grid, p), |&p| p == end).unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!((path[0], path[15]), ((0, 0), end));
+        assert!(path.windows(2).all(|w| open(&grid, w[0]).contains(&w[1])));
+
+        let path = dfs((0, 0), |&p| open(&grid, p), |&p| p == end).unwrap();
+        assert_eq!(path.last(), Some(&end));
+        assert_eq!(bfs((0, 0), |&p| open(&grid, p), |&p| p == (3, 5)), None);
+
+        let pocket = reachable([(3, 5)], |&p| open(&grid, p));
+        assert_eq!(pocket, HashSet::from([(3, 5)]));
+        assert_eq!(reachable([(0, 0)], |&p| open(&grid, p)).len(), 25);
+    }
+
+    #[test]
+    fn test_all_shortest_paths() {
+        // the shortest paths around the wall go left or right of it
+        let grid = Grid::parse("S...\n.##.\n...E\n", Some).unwrap();
+        let paths = bfs_all((0, 0), |&p| open(&grid, p));
+        assert_eq!(paths.cost[&(2, 3)], 5);
+        assert_eq!(paths.count_paths(&(2, 3)), Some(2));
+        assert_eq!(paths.count_paths(&(0, 0)), Some(1));
+        assert_eq!(paths.count_paths(&(1, 1)), Some(0));
+        assert_eq!(paths.nodes_on_paths(&(2, 3)).len(), 10);
+        assert_eq!(paths.nodes_on_paths(&(0, 3)).len(), 4);
+        assert_eq!(paths.predecessors[&(2, 3)], [(1, 3), (2, 2)]);
+
+        // C(58, 29) paths across a 30 by 30 grid, C(78, 39) overflow
+        let open_grid = |size: usize| Grid::filled(size, size, '.');
+        let grid = open_grid(30);
+        let paths = bfs_all((0, 0), |&p| open(&grid, p));
+        assert_eq!(paths.count_paths(&(29, 29)), Some(30_067_266_499_541_040));
+        let grid = open_grid(40);
+        let paths = bfs_all((0, 0), |&p| open(&grid, p));
+        assert_eq!(paths.count_paths(&(39, 39)), None);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // stepping onto a digit costs that many, onto anything else 1
+        let grid = Grid::parse("S19\n111\n91E\n", Some).unwrap();
+        let cost = |p: (usize, usize)| grid[p].to_digit(10).unwrap_or(1) as u64;
+        let successors = |&p: &(usize, usize)| {
+            grid.neighbors4(p)
+                .map(|next| (next, cost(next)))
+                .collect::<Vec<_>>()
+        };
+
+        let (path, total) = dijkstra((0, 0), successors, |&p| p == (2, 2)).unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(path.len(), 5);
+
+        let manhattan = |&(row, col): &(usize, usize)| (4 - row - col) as u64;
+        let (astar_path, astar_total) =
+            astar((0, 0), successors, manhattan, |&p| p == (2, 2)).unwrap();
+        assert_eq!((astar_path.len(), astar_total), (5, 4));
+
+        let distances = dijkstra_distances((0, 0), successors);
+        assert_eq!(distances[&(0, 2)], 10);
+        assert_eq!(distances[&(2, 0)], 10);
+        assert_eq!(distances.len(), 9);
+    }
+}
@@ -0,0 +1,12 @@
+//! Puzzles that advance one step at a time.
+//!
+//! A day implements [`Simulation`] for a structure that can be stepped and
+//! drawn as text, which is all the recorder in [`crate::asciicast`] needs.
+
+pub trait Simulation {
+    /// Advances by one step, returning `false` once the simulation has finished.
+    fn step(&mut self) -> bool;
+
+    /// The current state as text, one line per row.
+    fn render(&self) -> String;
+}
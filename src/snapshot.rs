@@ -0,0 +1,113 @@
+//! Snapshot tests of rendered output.
+//!
+//! A test renders a map or a report and hands it to [`assert_snapshot`],
+//! which compares it to the snapshot stored next to the example inputs, in
+//! `snapshot_dayNN_<name>.txt`. A mismatch fails the test with a line by line
+//! diff. Running the tests with `UPDATE_SNAPSHOTS=1` writes the rendered
+//! output to the snapshots instead, creating the missing ones:
+//!
+//! ```text
+//! UPDATE_SNAPSHOTS=1 cargo test --bin day08
+//! ```
+
+use std::fs;
+
+use crate::config::Config;
+
+pub const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// Compares `actual` to the snapshot `name` of a day, panicking with a diff
+/// when they differ.
+pub fn assert_snapshot(day: u8, name: &str, actual: &str) {
+    let config = Config::from_args(std::iter::empty()).expect("Invalid configuration");
+    let path = config.snapshot_path(day, name);
+
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        fs::write(&path, actual).expect("Unable to write the snapshot");
+        eprintln!("updated {}", path.display());
+        return;
+    }
+
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!(
+            "no snapshot {}, run the test with {UPDATE_ENV}=1 to create it",
+            path.display()
+        );
+    };
+    if let Some(diff) = diff(&expected, actual) {
+        panic!(
+            "the output differs from the snapshot {}, run the test with {UPDATE_ENV}=1 to accept it\n{diff}",
+            path.display()
+        );
+    }
+}
+
+/// A line by line diff from `expected` to `actual`, or `None` when they are
+/// the same. Removed lines start with `-`, added lines with `+`, each with
+/// its line number in the text it comes from.
+pub fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // longest common subsequences of the remaining lines
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {:>4} {}", i + 1, old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {:>4} {}", i + 1, old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {:>4} {}", j + 1, new[j]));
+            j += 1;
+        }
+    }
+    if old == new {
+        // the lines are the same, so the line endings differ
+        lines.push(String::from("(the line endings differ)"));
+    }
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), None);
+        assert_eq!(
+            diff("..#\n.#.\n#..\n", "..#\n.X.\n#..\n..."),
+            Some(
+                [
+                    "     1 ..#",
+                    "-    2 .#.",
+                    "+    2 .X.",
+                    "     3 #..",
+                    "+    4 ...",
+                ]
+                .join("\n")
+            )
+        );
+        assert_eq!(
+            diff("a\n", "a"),
+            Some(String::from("     1 a\n(the line endings differ)"))
+        );
+    }
+}
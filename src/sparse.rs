@@ -0,0 +1,230 @@
+//! Unbounded grids that only store the occupied cells.
+//!
+//! A [`SparseGrid`] maps points to cells, so it can grow in any direction
+//! and lookups off the map are fine: a missing cell reads as the default
+//! value of the grid. It keeps track of the bounding box of the stored
+//! cells, which is the region [`SparseGrid::to_grid`] and the `Display`
+//! implementation cover.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Index;
+
+use crate::direction::Direction;
+use crate::grid::Grid;
+use crate::point::Point;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    default: T,
+    /// The top left and bottom right corners of the stored cells, inclusive
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> SparseGrid<T> {
+    /// An empty grid, reading `default` everywhere.
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// Parses a grid with a cell per character, with the first line at
+    /// `y = 0`. Characters that `cell` maps to `None` are left out.
+    pub fn parse<F>(input: &str, default: T, mut cell: F) -> Self
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut grid = SparseGrid::new(default);
+        for (row, line) in input.lines().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                if let Some(value) = cell(c) {
+                    grid.insert(Point::from((row, col)), value);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// The top left and bottom right corners of the stored cells, inclusive,
+    /// or `None` for an empty grid.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// The stored cell at `point`.
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    /// The cell at `point`, or the default value when nothing is stored there.
+    pub fn value(&self, point: Point) -> &T {
+        self.get(point).unwrap_or(&self.default)
+    }
+
+    /// Stores a cell, returning the one it replaces.
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(extend(self.bounds, point));
+        self.cells.insert(point, value)
+    }
+
+    /// Removes a cell, shrinking the bounds when it was on their edge.
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+        if let Some((min, max)) = self.bounds {
+            if [min.x, max.x].contains(&point.x) || [min.y, max.y].contains(&point.y) {
+                self.bounds = self
+                    .cells
+                    .keys()
+                    .fold(None, |bounds, &point| Some(extend(bounds, point)));
+            }
+        }
+        Some(value)
+    }
+
+    /// Every stored cell with its point, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&point, cell)| (point, cell))
+    }
+
+    /// The points sharing a side with `point`, stored or not.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> {
+        Direction::CARDINAL
+            .into_iter()
+            .map(move |direction| point + direction)
+    }
+
+    /// The points sharing a side or a corner with `point`, stored or not.
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> {
+        Direction::iter().map(move |direction| point + direction)
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Every cell of a dense grid, with `(row, col)` at `Point::from((row, col))`.
+    pub fn from_grid(grid: &Grid<T>, default: T) -> Self {
+        let mut sparse = SparseGrid::new(default);
+        for (position, cell) in grid.iter() {
+            sparse.insert(Point::from(position), cell.clone());
+        }
+        sparse
+    }
+
+    /// The bounding box as a dense grid, with the default value in the
+    /// missing cells. Its top left cell is the top left corner of
+    /// [`SparseGrid::bounds`].
+    pub fn to_grid(&self) -> Grid<T> {
+        let Some((min, max)) = self.bounds else {
+            return Grid::filled(0, 0, self.default.clone());
+        };
+        let rows = (max.y - min.y + 1) as usize;
+        let cols = (max.x - min.x + 1) as usize;
+        Grid::from_fn(rows, cols, |row, col| {
+            self.value(min + Point::from((row, col))).clone()
+        })
+    }
+}
+
+/// The bounds grown to cover `point`.
+fn extend(bounds: Option<(Point, Point)>, point: Point) -> (Point, Point) {
+    match bounds {
+        None => (point, point),
+        Some((min, max)) => (
+            Point::new(min.x.min(point.x), min.y.min(point.y)),
+            Point::new(max.x.max(point.x), max.y.max(point.y)),
+        ),
+    }
+}
+
+/// Reads the default value for missing cells, so it never panics.
+impl<T> Index<Point> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.value(point)
+    }
+}
+
+/// The rows of the bounding box, with the default value in the missing
+/// cells.
+impl<T: fmt::Display> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((min, max)) = self.bounds else {
+            return Ok(());
+        };
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                write!(f, "{}", self.value(Point::new(x, y)))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = SparseGrid::parse("..#\n#..\n", '.', |c| (c == '#').then_some(c));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some((Point::new(0, 0), Point::new(2, 1))));
+        assert_eq!(grid[Point::new(-5, 7)], '.');
+        assert_eq!(grid.get(Point::new(1, 0)), None);
+
+        grid.insert(Point::new(-2, 3), '@');
+        assert_eq!(grid.bounds(), Some((Point::new(-2, 0), Point::new(2, 3))));
+        assert_eq!(grid.to_string(), "....#\n..#..\n.....\n@....\n");
+
+        grid.remove(Point::new(-2, 3));
+        assert_eq!(grid.to_string(), "..#\n#..\n");
+        grid.remove(Point::new(2, 0));
+        grid.remove(Point::new(0, 1));
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.to_string(), "");
+    }
+
+    #[test]
+    fn test_dense() {
+        let dense = Grid::parse("ab\ncd\n", Some).unwrap();
+        let mut sparse = SparseGrid::from_grid(&dense, ' ');
+        assert_eq!(sparse[Point::new(1, 0)], 'b');
+        assert_eq!(sparse.to_grid(), dense);
+
+        sparse.remove(Point::new(0, 0));
+        sparse.remove(Point::new(0, 1));
+        assert_eq!(sparse.to_grid().to_string(), "b\nd\n");
+        assert_eq!(
+            sparse
+                .neighbors8(Point::ZERO)
+                .filter(|&p| sparse.contains(p))
+                .collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(1, 1)]
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Files of serialized state, like the progress and the bench history.
+//!
+//! A missing file reads as the default value, so the first run of a command
+//! starts out empty. Errors name the file they came from.
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reads and parses `path`, or returns the default when it does not exist.
+pub fn load<T>(path: &Path) -> Result<T, TomlError>
+where
+    T: Default + for<'de> Deserialize<'de>,
+{
+    match fs::read_to_string(path) {
+        Ok(text) => toml::from_str(&text).map_err(|source| TomlError::Parse {
+            path: path.to_path_buf(),
+            source,
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(source) => Err(TomlError::Io {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+pub fn save<T: Serialize>(value: &T, path: &Path) -> Result<(), TomlError> {
+    let text = toml::to_string(value).expect("Unable to serialize");
+    fs::write(path, text).map_err(|source| TomlError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[derive(Debug)]
+pub enum TomlError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TomlError::Io { path, source } => {
+                write!(f, "could not access {}: {source}", path.display())
+            }
+            TomlError::Parse { path, source } => {
+                write!(f, "invalid file {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for TomlError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_missing_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("no_such_file.toml");
+        let loaded: BTreeMap<String, u32> = load(&path).unwrap();
+        assert!(loaded.is_empty());
+
+        let err = save(&loaded, &path.join("nested.toml")).unwrap_err();
+        assert!(err.to_string().starts_with("could not access"));
+    }
+}
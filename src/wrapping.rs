@@ -0,0 +1,125 @@
+//! Grids whose edges wrap around, like the surface of a torus.
+//!
+//! A [`WrappingGrid`] is a view of a [`Grid`] where stepping off one edge
+//! comes back in on the opposite edge. Any point is on the grid, wrapped
+//! with modular arithmetic, so lookups and steps never fail.
+
+use std::iter;
+use std::ops::Index;
+
+use crate::direction::Direction;
+use crate::grid::Grid;
+use crate::point::Point;
+
+#[derive(Debug, Clone, Copy)]
+pub struct WrappingGrid<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<'a, T> WrappingGrid<'a, T> {
+    /// A wrapping view of `grid`, which can't be empty.
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        assert!(
+            grid.rows() > 0 && grid.cols() > 0,
+            "an empty grid can't wrap around"
+        );
+        WrappingGrid { grid }
+    }
+
+    pub fn grid(&self) -> &'a Grid<T> {
+        self.grid
+    }
+
+    /// The position on the grid that `point` wraps around to.
+    pub fn wrap(&self, point: Point) -> (usize, usize) {
+        (
+            point.y.rem_euclid(self.grid.rows() as i64) as usize,
+            point.x.rem_euclid(self.grid.cols() as i64) as usize,
+        )
+    }
+
+    pub fn get(&self, point: Point) -> &'a T {
+        &self.grid[self.wrap(point)]
+    }
+
+    /// The position a step in `direction` away, wrapping around the edges.
+    pub fn step(&self, position: (usize, usize), direction: Direction) -> (usize, usize) {
+        self.wrap(Point::from(position) + direction)
+    }
+
+    /// The positions of the cells sharing a side with `position`. On grids
+    /// thinner than three cells some of them are the same.
+    pub fn neighbors4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .map(move |direction| self.step(position, direction))
+    }
+
+    /// The positions of the cells sharing a side or a corner with `position`.
+    pub fn neighbors8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::iter().map(move |direction| self.step(position, direction))
+    }
+
+    /// The positions from `start` on in `direction`, without end: the line
+    /// comes back to `start` after going around the grid.
+    pub fn line(
+        &self,
+        start: (usize, usize),
+        direction: Direction,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        iter::successors(Some(start), move |&position| {
+            Some(self.step(position, direction))
+        })
+    }
+}
+
+impl<T> Index<Point> for WrappingGrid<'_, T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        let grid = Grid::parse("abc\ndef\n", Some).unwrap();
+        let wrapping = WrappingGrid::new(&grid);
+        assert_eq!(wrapping.wrap(Point::new(-1, -1)), (1, 2));
+        assert_eq!(wrapping[Point::new(7, 4)], 'b');
+        assert_eq!(wrapping.step((0, 0), Direction::NW), (1, 2));
+        assert_eq!(
+            wrapping.neighbors4((0, 2)).collect::<Vec<_>>(),
+            [(1, 2), (0, 0), (1, 2), (0, 1)]
+        );
+        assert_eq!(wrapping.neighbors8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_line() {
+        let grid = Grid::parse("abc\ndef\n", Some).unwrap();
+        let wrapping = grid.wrapping();
+        let line: String = wrapping
+            .line((0, 1), Direction::E)
+            .take(5)
+            .map(|position| grid[position])
+            .collect();
+        assert_eq!(line, "bcabc");
+        let line: String = wrapping
+            .line((1, 0), Direction::SW)
+            .take(6)
+            .map(|position| grid[position])
+            .collect();
+        assert_eq!(line, "dceafb");
+    }
+}